@@ -9,6 +9,7 @@ use embedded_graphics_core::pixelcolor::BinaryColor;
 use embedded_graphics_core::pixelcolor::PixelColor;
 
 /// When trying to parse u8 to one of the color types
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, PartialEq, Eq)]
 pub struct OutOfColorRangeParseError(u8);
 impl core::fmt::Display for OutOfColorRangeParseError {
@@ -25,6 +26,7 @@ impl OutOfColorRangeParseError {
 
 /// Only for the Black/White-Displays
 // TODO : 'color' is not a good name for black and white, rename it to BiColor/BWColor ?
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum Color {
     /// Black color
@@ -35,6 +37,7 @@ pub enum Color {
 }
 
 /// Only for the Black/White/Color-Displays
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum TriColor {
     /// Black color
@@ -47,6 +50,7 @@ pub enum TriColor {
 }
 
 /// Only for the Black/White/Red/Yellow-Displays
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum QuadColor {
     /// Black color
@@ -61,6 +65,7 @@ pub enum QuadColor {
 }
 
 /// For the 7 Color Displays
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum OctColor {
     /// Black Color
@@ -82,6 +87,49 @@ pub enum OctColor {
     HiZ = 0x07,
 }
 
+/// For the 6 Color (Spectra 6 / E6) Displays
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HexColor {
+    /// Black Color
+    Black = 0x00,
+    /// White Color
+    #[default]
+    White = 0x01,
+    /// Yellow Color
+    Yellow = 0x02,
+    /// Red Color
+    Red = 0x03,
+    /// Blue Color
+    Blue = 0x05,
+    /// Green Color
+    Green = 0x06,
+}
+
+/// For the 4-grey modes of black/white displays
+///
+/// Every pixel uses 2 bits, the value is the luma of the pixel.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Gray4Color {
+    /// Black color
+    Black = 0b00,
+    /// Dark gray color
+    DarkGray = 0b01,
+    /// Light gray color
+    LightGray = 0b10,
+    /// White color
+    #[default]
+    White = 0b11,
+}
+
+/// For 16 level grayscale displays like the IT8951 panels
+///
+/// Every pixel uses 4 bits, the value is the luma of the pixel from 0 (black) to 15 (white).
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Gray16Color(u8);
+
 /// Color trait for use in `Display`s
 pub trait ColorType {
     /// Number of bit used to represent this color type in a single buffer.
@@ -92,6 +140,11 @@ pub trait ColorType {
     /// splitted buffer like tricolo is 2, otherwise this should be 1.
     const BUFFER_COUNT: usize;
 
+    /// Byte value a new buffer is filled with.
+    ///
+    /// Every pixel of this byte must hold the same color, for most color types this is 0.
+    const DEFAULT_BYTE: u8 = 0;
+
     /// Return the data used to set a pixel color
     ///
     /// * bwrbit is used to tell the value of the unused bit when a chromatic
@@ -101,7 +154,7 @@ pub trait ColorType {
     /// Return values are :
     /// * .0 is the mask used to exclude this pixel from the byte (eg: 0x7F in BiColor)
     /// * .1 are the bits used to set the color in the byte (eg: 0x80 in BiColor)
-    ///      this is u16 because we set 2 bytes in case of split buffer
+    ///   this is u16 because we set 2 bytes in case of split buffer
     fn bitmask(&self, bwrbit: bool, pos: u32) -> (u8, u16);
 
     /// convert 0 -> black, 1 -> white for binary color
@@ -162,6 +215,8 @@ impl ColorType for QuadColor {
     const BITS_PER_PIXEL_PER_BUFFER: usize = 2;
     // 使用1个缓冲区来存储2位颜色信息
     const BUFFER_COUNT: usize = 1;
+    // 白色是0b01，新缓冲区默认填充白色
+    const DEFAULT_BYTE: u8 = 0x55;
 
     fn bitmask(&self, _bwrbit: bool, pos: u32) -> (u8, u16) {
         // yrd0750ryf665f60墨水屏的颜色顺序是 Pixel 1->4，其中Pixel4占据Bit6~7，和平时的认知是反过来的
@@ -175,13 +230,7 @@ impl ColorType for QuadColor {
         // 掩码：清除当前像素的2位
         let mask = !(0x03 << shift);
         // 根据颜色获取对应的2位值
-        let color_bits = match self {
-            // 这里的颜色值只在yrd0750ryf665f60的R50H寄存器的DDX为1时有效，如果该寄存器为0，这里的颜色顺序要相反
-            QuadColor::Black => 0b00,  // 黑色 0b00
-            QuadColor::White => 0b01,  // 白色 0b01
-            QuadColor::Yellow => 0b10, // 黄色 0b10
-            QuadColor::Red => 0b11,    // 红色 0b11
-        };
+        let color_bits = self.get_bits();
         // 将颜色值移到正确的位置
         let value = (color_bits << shift) as u16;
 
@@ -208,13 +257,55 @@ impl ColorType for OctColor {
     }
 
     fn from_bits(bits: u8) -> Self {
-        match bits {
-            0x00 => OctColor::Black,
-            0x01 => OctColor::White,
-            0x02 => OctColor::Yellow,
-            // TODO:
-            _ => OctColor::Red,
-        }
+        OctColor::from_nibble(bits).unwrap_or_default()
+    }
+}
+
+impl ColorType for Gray4Color {
+    const BITS_PER_PIXEL_PER_BUFFER: usize = 2;
+    const BUFFER_COUNT: usize = 1;
+    const DEFAULT_BYTE: u8 = 0xFF;
+
+    fn bitmask(&self, _bwrbit: bool, pos: u32) -> (u8, u16) {
+        // first pixel in the most significant bits
+        let shift = 6 - (pos % 4) * 2;
+        let mask = !(0x03 << shift);
+        (mask, (self.get_bits() << shift) as u16)
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Gray4Color::from_luma(bits)
+    }
+}
+
+impl ColorType for Gray16Color {
+    const BITS_PER_PIXEL_PER_BUFFER: usize = 4;
+    const BUFFER_COUNT: usize = 1;
+    const DEFAULT_BYTE: u8 = 0xFF;
+
+    fn bitmask(&self, _bwrbit: bool, pos: u32) -> (u8, u16) {
+        // first pixel in the most significant bits
+        let shift = 4 - (pos % 2) * 4;
+        let mask = !(0x0F << shift);
+        (mask, (self.luma() << shift) as u16)
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Gray16Color::new(bits)
+    }
+}
+
+impl ColorType for HexColor {
+    const BITS_PER_PIXEL_PER_BUFFER: usize = 4;
+    const BUFFER_COUNT: usize = 1;
+    fn bitmask(&self, _bwrbit: bool, pos: u32) -> (u8, u16) {
+        let mask = !(0xF0 >> ((pos % 2) * 4));
+        let bits = self.get_nibble() as u16;
+        (mask, if pos % 2 == 1 { bits } else { bits << 4 })
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        HexColor::from_nibble(bits).unwrap_or_default()
     }
 }
 
@@ -328,6 +419,112 @@ impl OctColor {
         }
     }
 }
+
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for HexColor {
+    fn from(b: BinaryColor) -> HexColor {
+        match b {
+            BinaryColor::On => HexColor::Black,
+            BinaryColor::Off => HexColor::White,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<HexColor> for embedded_graphics_core::pixelcolor::Rgb888 {
+    fn from(b: HexColor) -> Self {
+        let (r, g, b) = b.rgb();
+        Self::new(r, g, b)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Rgb888> for HexColor {
+    fn from(p: embedded_graphics_core::pixelcolor::Rgb888) -> HexColor {
+        use embedded_graphics_core::prelude::RgbColor;
+        let colors = [
+            HexColor::Black,
+            HexColor::White,
+            HexColor::Yellow,
+            HexColor::Red,
+            HexColor::Blue,
+            HexColor::Green,
+        ];
+        // if the user has already mapped to the right color space, it will just be in the list
+        if let Some(found) = colors.iter().find(|c| c.rgb() == (p.r(), p.g(), p.b())) {
+            return *found;
+        }
+
+        // This is not ideal but just pick the nearest color
+        *colors
+            .iter()
+            .map(|c| (c, c.rgb()))
+            .map(|(c, (r, g, b))| {
+                let dist = (i32::from(r) - i32::from(p.r())).pow(2)
+                    + (i32::from(g) - i32::from(p.g())).pow(2)
+                    + (i32::from(b) - i32::from(p.b())).pow(2);
+                (c, dist)
+            })
+            .min_by_key(|(_c, dist)| *dist)
+            .map(|(c, _)| c)
+            .unwrap_or(&HexColor::White)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::raw::RawU4> for HexColor {
+    fn from(b: embedded_graphics_core::pixelcolor::raw::RawU4) -> Self {
+        use embedded_graphics_core::prelude::RawData;
+        HexColor::from_nibble(b.into_inner()).unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl PixelColor for HexColor {
+    type Raw = embedded_graphics_core::pixelcolor::raw::RawU4;
+}
+
+impl HexColor {
+    /// Gets the Nibble representation of the Color as needed by the display
+    pub fn get_nibble(self) -> u8 {
+        self as u8
+    }
+    /// Converts two colors into a single byte for the Display
+    pub fn colors_byte(a: HexColor, b: HexColor) -> u8 {
+        a.get_nibble() << 4 | b.get_nibble()
+    }
+
+    ///Take the nibble (lower 4 bits) and convert to a HexColor if possible
+    pub fn from_nibble(nibble: u8) -> Result<HexColor, OutOfColorRangeParseError> {
+        match nibble & 0xf {
+            0x00 => Ok(HexColor::Black),
+            0x01 => Ok(HexColor::White),
+            0x02 => Ok(HexColor::Yellow),
+            0x03 => Ok(HexColor::Red),
+            0x05 => Ok(HexColor::Blue),
+            0x06 => Ok(HexColor::Green),
+            e => Err(OutOfColorRangeParseError(e)),
+        }
+    }
+    ///Split the nibbles of a single byte and convert both to a HexColor if possible
+    pub fn split_byte(byte: u8) -> Result<(HexColor, HexColor), OutOfColorRangeParseError> {
+        let low = HexColor::from_nibble(byte & 0xf)?;
+        let high = HexColor::from_nibble((byte >> 4) & 0xf)?;
+        Ok((high, low))
+    }
+    /// Converts to limited range of RGB values.
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            HexColor::White => (0xff, 0xff, 0xff),
+            HexColor::Black => (0x00, 0x00, 0x00),
+            HexColor::Yellow => (0xff, 0xff, 0x00),
+            HexColor::Red => (0xff, 0x00, 0x00),
+            HexColor::Blue => (0x00, 0x00, 0xff),
+            HexColor::Green => (0x00, 0xff, 0x00),
+        }
+    }
+}
+
 //TODO: Rename get_bit_value to bit() and get_byte_value to byte() ?
 
 impl Color {
@@ -579,6 +776,20 @@ impl PixelColor for QuadColor {
 }
 
 impl QuadColor {
+    /// Gets the 2 bit representation of the color
+    ///
+    /// Black is `0b00`, white `0b01`, yellow `0b10` and red `0b11`, the color codes of the
+    /// yrd0750ryf665f60 and the Waveshare G panels.
+    pub fn get_bits(self) -> u8 {
+        match self {
+            // 这里的颜色值只在yrd0750ryf665f60的R50H寄存器的DDX为1时有效，如果该寄存器为0，这里的颜色顺序要相反
+            QuadColor::Black => 0b00,  // 黑色 0b00
+            QuadColor::White => 0b01,  // 白色 0b01
+            QuadColor::Yellow => 0b10, // 黄色 0b10
+            QuadColor::Red => 0b11,    // 红色 0b11
+        }
+    }
+
     /// Get the color encoding of the color for one bit
     pub fn get_bit_value(self) -> u8 {
         match self {
@@ -647,6 +858,178 @@ impl From<QuadColor> for embedded_graphics_core::pixelcolor::Rgb888 {
     }
 }
 
+impl Gray4Color {
+    /// Gets the 2 bit representation of the color
+    pub fn get_bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Converts the lower 2 bits of a luma value into a color
+    pub fn from_luma(luma: u8) -> Gray4Color {
+        match luma & 0b11 {
+            0b00 => Gray4Color::Black,
+            0b01 => Gray4Color::DarkGray,
+            0b10 => Gray4Color::LightGray,
+            _ => Gray4Color::White,
+        }
+    }
+
+    /// Packs one bit plane of up to 8 pixels of a 4-grey buffer into a single byte
+    ///
+    /// `shift` selects the plane: 1 for the high bit of each pixel, 0 for the low bit.
+    /// Most controllers take the two planes of a 4-grey image in their two RAMs.
+    pub(crate) fn plane_byte(pixels: &[u8], shift: u8) -> u8 {
+        pixels.iter().fold(0, |acc, &byte| {
+            (0..4)
+                .rev()
+                .fold(acc, |acc, i| (acc << 1) | ((byte >> (i * 2 + shift)) & 1))
+        })
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl PixelColor for Gray4Color {
+    type Raw = embedded_graphics_core::pixelcolor::raw::RawU2;
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::raw::RawU2> for Gray4Color {
+    fn from(raw: embedded_graphics_core::pixelcolor::raw::RawU2) -> Self {
+        Gray4Color::from_luma(embedded_graphics_core::prelude::RawData::into_inner(raw))
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for Gray4Color {
+    fn from(b: BinaryColor) -> Gray4Color {
+        match b {
+            BinaryColor::On => Gray4Color::Black,
+            BinaryColor::Off => Gray4Color::White,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Gray2> for Gray4Color {
+    fn from(gray: embedded_graphics_core::pixelcolor::Gray2) -> Self {
+        use embedded_graphics_core::pixelcolor::GrayColor;
+        Gray4Color::from_luma(gray.luma())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<Gray4Color> for embedded_graphics_core::pixelcolor::Gray2 {
+    fn from(gray: Gray4Color) -> Self {
+        Self::new(gray.get_bits())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Rgb888> for Gray4Color {
+    fn from(rgb: embedded_graphics_core::pixelcolor::Rgb888) -> Self {
+        use embedded_graphics_core::pixelcolor::RgbColor;
+        // ITU-R BT.601 luma, scaled down to 2 bits
+        let luma =
+            (u32::from(rgb.r()) * 299 + u32::from(rgb.g()) * 587 + u32::from(rgb.b()) * 114) / 1000;
+        Gray4Color::from_luma((luma >> 6) as u8)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<Gray4Color> for embedded_graphics_core::pixelcolor::Rgb888 {
+    fn from(gray: Gray4Color) -> Self {
+        let luma = gray.get_bits() * 0x55;
+        Self::new(luma, luma, luma)
+    }
+}
+
+impl Gray16Color {
+    /// Black color
+    pub const BLACK: Gray16Color = Gray16Color(0x0);
+    /// White color
+    pub const WHITE: Gray16Color = Gray16Color(0xF);
+
+    /// Creates a color from the lower 4 bits of a luma value
+    pub const fn new(luma: u8) -> Gray16Color {
+        Gray16Color(luma & 0x0F)
+    }
+
+    /// Gets the 4 bit luma of the color
+    pub fn luma(self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for Gray16Color {
+    fn default() -> Self {
+        Gray16Color::WHITE
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl PixelColor for Gray16Color {
+    type Raw = embedded_graphics_core::pixelcolor::raw::RawU4;
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::raw::RawU4> for Gray16Color {
+    fn from(raw: embedded_graphics_core::pixelcolor::raw::RawU4) -> Self {
+        Gray16Color::new(embedded_graphics_core::prelude::RawData::into_inner(raw))
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for Gray16Color {
+    fn from(b: BinaryColor) -> Gray16Color {
+        match b {
+            BinaryColor::On => Gray16Color::BLACK,
+            BinaryColor::Off => Gray16Color::WHITE,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Gray4> for Gray16Color {
+    fn from(gray: embedded_graphics_core::pixelcolor::Gray4) -> Self {
+        use embedded_graphics_core::pixelcolor::GrayColor;
+        Gray16Color::new(gray.luma())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<Gray16Color> for embedded_graphics_core::pixelcolor::Gray4 {
+    fn from(gray: Gray16Color) -> Self {
+        Self::new(gray.luma())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Gray8> for Gray16Color {
+    fn from(gray: embedded_graphics_core::pixelcolor::Gray8) -> Self {
+        use embedded_graphics_core::pixelcolor::GrayColor;
+        Gray16Color::new(gray.luma() >> 4)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Rgb888> for Gray16Color {
+    fn from(rgb: embedded_graphics_core::pixelcolor::Rgb888) -> Self {
+        use embedded_graphics_core::pixelcolor::RgbColor;
+        // ITU-R BT.601 luma, scaled down to 4 bits
+        let luma =
+            (u32::from(rgb.r()) * 299 + u32::from(rgb.g()) * 587 + u32::from(rgb.b()) * 114) / 1000;
+        Gray16Color::new((luma >> 4) as u8)
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<Gray16Color> for embedded_graphics_core::pixelcolor::Rgb888 {
+    fn from(gray: Gray16Color) -> Self {
+        let luma = gray.luma() * 0x11;
+        Self::new(luma, luma, luma)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -689,6 +1072,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_oct_from_bits() {
+        for nibble in 0..8 {
+            let color = OctColor::from_nibble(nibble).unwrap();
+            assert_eq!(OctColor::from_bits(color.get_nibble()), color);
+        }
+        assert_eq!(OctColor::from_bits(0x0F), OctColor::White);
+    }
+
+    #[test]
+    fn test_hex() {
+        let left = HexColor::Blue;
+        let right = HexColor::Yellow;
+        assert_eq!(
+            HexColor::split_byte(HexColor::colors_byte(left, right)),
+            Ok((left, right))
+        );
+        assert_eq!(HexColor::colors_byte(left, right), 0x52);
+        assert_eq!(
+            HexColor::from_nibble(0x04),
+            Err(OutOfColorRangeParseError(0x04))
+        );
+    }
+
+    #[test]
+    fn test_gray4() {
+        let (mask, bits) = Gray4Color::LightGray.bitmask(false, 1);
+        assert_eq!(mask, 0b1100_1111);
+        assert_eq!(bits, 0b0010_0000);
+        assert_eq!(Gray4Color::from_bits(0b01), Gray4Color::DarkGray);
+        assert_eq!(Gray4Color::DEFAULT_BYTE, 0xFF);
+    }
+
+    #[test]
+    fn test_gray4_planes() {
+        // white, light gray, dark gray, black, black, dark gray, light gray, white
+        let pixels = [0b1110_0100, 0b0001_1011];
+        assert_eq!(Gray4Color::plane_byte(&pixels, 1), 0b1100_0011);
+        assert_eq!(Gray4Color::plane_byte(&pixels, 0), 0b1010_0101);
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn test_gray4_conversions() {
+        use embedded_graphics_core::pixelcolor::{Gray2, Rgb888};
+        assert_eq!(Gray4Color::from(Gray2::new(1)), Gray4Color::DarkGray);
+        assert_eq!(Gray2::from(Gray4Color::LightGray), Gray2::new(2));
+        assert_eq!(
+            Gray4Color::from(Rgb888::new(0xff, 0xff, 0xff)),
+            Gray4Color::White
+        );
+        assert_eq!(
+            Gray4Color::from(Rgb888::new(0x80, 0x80, 0x80)),
+            Gray4Color::LightGray
+        );
+        assert_eq!(
+            Rgb888::from(Gray4Color::DarkGray),
+            Rgb888::new(0x55, 0x55, 0x55)
+        );
+    }
+
+    #[test]
+    fn test_gray16() {
+        let (mask, bits) = Gray16Color::new(0x6).bitmask(false, 1);
+        assert_eq!(mask, 0xF0);
+        assert_eq!(bits, 0x06);
+        let (mask, bits) = Gray16Color::new(0x6).bitmask(false, 2);
+        assert_eq!(mask, 0x0F);
+        assert_eq!(bits, 0x60);
+        assert_eq!(Gray16Color::from_bits(0x1F), Gray16Color::WHITE);
+        assert_eq!(Gray16Color::default(), Gray16Color::WHITE);
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn test_gray16_conversions() {
+        use embedded_graphics_core::pixelcolor::{Gray4, Gray8, Rgb888};
+        assert_eq!(Gray16Color::from(Gray4::new(7)), Gray16Color::new(7));
+        assert_eq!(Gray4::from(Gray16Color::new(9)), Gray4::new(9));
+        assert_eq!(Gray16Color::from(Gray8::new(0x80)), Gray16Color::new(8));
+        assert_eq!(
+            Gray16Color::from(Rgb888::new(0xff, 0xff, 0xff)),
+            Gray16Color::WHITE
+        );
+        assert_eq!(
+            Rgb888::from(Gray16Color::new(3)),
+            Rgb888::new(0x33, 0x33, 0x33)
+        );
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn test_hex_rgb() {
+        use embedded_graphics_core::pixelcolor::Rgb888;
+        assert_eq!(HexColor::from(Rgb888::new(0, 0, 0xff)), HexColor::Blue);
+        assert_eq!(HexColor::from(Rgb888::new(0xf0, 0x10, 0x20)), HexColor::Red);
+        assert_eq!(Rgb888::from(HexColor::Green), Rgb888::new(0, 0xff, 0));
+    }
+
     #[test]
     fn test_tricolor_bitmask() {
         assert_eq!(
@@ -0,0 +1,92 @@
+//! SPI Commands for the Waveshare 12.48" E-Ink Display
+
+use crate::traits;
+
+/// Epd12in48 commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// All four controllers of the panel share this command set.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Set Resolution, LUT selection, BWR pixels, gate scan direction, source shift
+    /// direction, booster switch, soft reset.
+    PanelSetting = 0x00,
+
+    /// Selecting internal and external power
+    PowerSetting = 0x01,
+
+    /// After the Power Off command, the driver will power off following the Power Off
+    /// Sequence; BUSY signal will become "0".
+    PowerOff = 0x02,
+
+    /// Turning On the Power
+    PowerOn = 0x04,
+
+    /// Starting data transmission
+    BoosterSoftStart = 0x06,
+
+    /// This command makes the chip enter the deep-sleep mode to save power.
+    ///
+    /// The only one parameter is a check code, the command would be excuted if check code = 0xA5.
+    DeepSleep = 0x07,
+
+    /// This command starts transmitting the old data and writes them to SRAM.
+    DataStartTransmission1 = 0x10,
+
+    /// After this command is issued, driver will refresh display (data/VCOM) according to
+    /// SRAM data and LUT.
+    DisplayRefresh = 0x12,
+
+    /// This command starts transmitting the new data and writes them to SRAM.
+    DataStartTransmission2 = 0x13,
+
+    /// Dual SPI
+    DualSpi = 0x15,
+
+    /// This command reads the temperature sensed by the temperature sensor.
+    TemperatureSensorCalibration = 0x40,
+
+    /// This command indicates the interval of Vcom and data output.
+    VcomAndDataIntervalSetting = 0x50,
+
+    /// This command sets the non-overlap period of Gate and Source.
+    TconSetting = 0x60,
+
+    /// This command defines alternative resolution and this setting is of higher priority
+    /// than the RES\[1:0\] in R00H (PSR).
+    TconResolution = 0x61,
+
+    /// This command reads the IC status
+    GetStatus = 0x71,
+
+    /// Cascade setting, lets the controllers use a forced temperature
+    CascadeSetting = 0xE0,
+
+    /// Power saving
+    PowerSaving = 0xE3,
+
+    /// Forced temperature value used by all cascaded controllers
+    ForceTemperature = 0xE5,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::PanelSetting.address(), 0x00);
+        assert_eq!(Command::DataStartTransmission2.address(), 0x13);
+        assert_eq!(Command::ForceTemperature.address(), 0xE5);
+    }
+}
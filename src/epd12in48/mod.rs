@@ -0,0 +1,427 @@
+//! A simple Driver for the Waveshare 12.48" E-Ink Display via SPI
+//!
+//! The panel is made of four quadrants, each one driven by its own controller:
+//!
+//! ```text
+//! +------------+-------------+
+//! | S2 648x492 | M2 656x492  |
+//! +------------+-------------+
+//! | M1 648x492 | S1 656x492  |
+//! +------------+-------------+
+//! ```
+//!
+//! Every controller sits behind its own chip select and reports through its own BUSY
+//! line, while DC and RST are shared. The driver therefore owns four [`SpiDevice`]s
+//! (one per chip select, e.g. `RefCellDevice`s of `embedded-hal-bus` on a single bus)
+//! and four BUSY pins, ordered `[M1, S1, M2, S2]`. The upper quadrants are mounted
+//! upside down, their controllers (M2 and S2) are set to the reversed gate and source
+//! scan directions so a plain [`Display12in48`] buffer can be used for the whole panel.
+//!
+//! Unlike the other drivers this one doesn't implement
+//! [`WaveshareDisplay`](crate::traits::WaveshareDisplay), as it owns its SPI devices.
+//! [`DisplaySettings`](crate::traits::DisplaySettings) is implemented as usual.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/12.48inch_e-Paper_Module)
+//! - [Waveshare C driver](https://github.com/waveshare/12.48inch-e-paper/blob/master/RaspberryPi/c/lib/e-Paper/EPD_12in48.c)
+
+use core::ops::Range;
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::color::Color;
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+use crate::trace::TraceEvent;
+
+pub(crate) mod command;
+use self::command::Command;
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 12in48 EPD
+#[cfg(feature = "graphics")]
+pub type Display12in48 = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize, HEIGHT as usize) },
+    Color,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 1304;
+/// Height of the display
+pub const HEIGHT: u32 = 984;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+/// Temperature (in °C) forced onto all controllers until [`Epd12in48::set_temperature`]
+/// is used
+pub const DEFAULT_TEMPERATURE: i8 = 20;
+const IS_BUSY_LOW: bool = true;
+const RESET: ResetProfile = ResetProfile::new(10_000, 2_000);
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// Bytes of one line of the whole panel
+const LINE_BYTES: usize = WIDTH as usize / 8;
+/// Bytes of one line of the left quadrants (M1 and S2)
+const LEFT_LINE_BYTES: usize = 81;
+/// Lines of every quadrant
+const QUADRANT_HEIGHT: usize = HEIGHT as usize / 2;
+
+/// The four controllers of the panel
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Controller {
+    /// Bottom left quadrant
+    M1 = 0,
+    /// Bottom right quadrant
+    S1 = 1,
+    /// Top right quadrant
+    M2 = 2,
+    /// Top left quadrant
+    S2 = 3,
+}
+
+impl Controller {
+    const ALL: [Controller; 4] = [
+        Controller::M1,
+        Controller::S1,
+        Controller::M2,
+        Controller::S2,
+    ];
+
+    /// Name of the controller in the traces
+    fn name(self) -> &'static str {
+        match self {
+            Controller::M1 => "M1",
+            Controller::S1 => "S1",
+            Controller::M2 => "M2",
+            Controller::S2 => "S2",
+        }
+    }
+
+    /// Byte columns and lines of the full frame buffer handled by this controller
+    fn quadrant(self) -> (Range<usize>, Range<usize>) {
+        let left = 0..LEFT_LINE_BYTES;
+        let right = LEFT_LINE_BYTES..LINE_BYTES;
+        let top = 0..QUADRANT_HEIGHT;
+        let bottom = QUADRANT_HEIGHT..HEIGHT as usize;
+        match self {
+            Controller::M1 => (left, bottom),
+            Controller::S1 => (right, bottom),
+            Controller::M2 => (right, top),
+            Controller::S2 => (left, top),
+        }
+    }
+
+    /// Panel setting: the top quadrants scan in the opposite directions
+    fn panel_setting(self) -> u8 {
+        match self {
+            Controller::M1 | Controller::S1 => 0x1F,
+            Controller::M2 | Controller::S2 => 0x13,
+        }
+    }
+}
+
+/// Epd12in48 driver
+///
+pub struct Epd12in48<SPI, BUSY, DC, RST, DELAY> {
+    /// Connection Interface, shared DC and RST as well as the BUSY pin of M1
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// SPI devices ordered `[M1, S1, M2, S2]`
+    spi: [SPI; 4],
+    /// BUSY pins of S1, M2 and S2
+    busy: [BUSY; 3],
+    /// Temperature forced onto all controllers
+    temperature: i8,
+    /// Background Color
+    color: Color,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST> for Epd12in48<SPI, BUSY, DC, RST, DELAY> {
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> Epd12in48<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Creates a new driver from the four SPI devices and BUSY pins (ordered
+    /// `[M1, S1, M2, S2]`), the shared DC and RST pins
+    ///
+    /// This already initialises the device.
+    pub fn new(
+        spi: [SPI; 4],
+        busy: [BUSY; 4],
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let [busy_m1, busy_s1, busy_m2, busy_s2] = busy;
+        let interface = DisplayInterface::new(busy_m1, dc, rst, delay_us);
+
+        let mut epd = Epd12in48 {
+            interface,
+            spi,
+            busy: [busy_s1, busy_m2, busy_s2],
+            temperature: DEFAULT_TEMPERATURE,
+            color: DEFAULT_BACKGROUND_COLOR,
+        };
+
+        epd.init(delay)?;
+
+        Ok(epd)
+    }
+
+    fn init(&mut self, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Reset all four controllers
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
+
+        for c in Controller::ALL {
+            self.cmd_with_data(c, Command::PanelSetting, &[c.panel_setting()])?;
+        }
+
+        // only the masters drive the boosters
+        for c in [Controller::M1, Controller::M2] {
+            self.cmd_with_data(c, Command::BoosterSoftStart, &[0x17, 0x17, 0x39, 0x17])?;
+        }
+
+        for c in Controller::ALL {
+            let (columns, lines) = c.quadrant();
+            let w = (columns.len() * 8) as u16;
+            let h = lines.len() as u16;
+            self.cmd_with_data(
+                c,
+                Command::TconResolution,
+                &[(w >> 8) as u8, w as u8, (h >> 8) as u8, h as u8],
+            )?;
+        }
+
+        for c in Controller::ALL {
+            self.cmd_with_data(c, Command::DualSpi, &[0x20])?;
+            self.cmd_with_data(c, Command::VcomAndDataIntervalSetting, &[0x21, 0x07])?;
+            self.cmd_with_data(c, Command::TconSetting, &[0x22])?;
+            self.cmd_with_data(c, Command::PowerSaving, &[0x00])?;
+        }
+
+        self.send_temperature()
+    }
+
+    /// Wakes the device up and re-initialises it
+    pub fn wake_up(&mut self, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(delay)
+    }
+
+    /// Powers off all four controllers and puts them into deep sleep
+    ///
+    /// Only a reset (e.g. [`wake_up`](Self::wake_up)) brings them back.
+    pub fn sleep(&mut self, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        for c in Controller::ALL {
+            self.command(c, Command::PowerOff)?;
+        }
+        self.wait_until_idle(delay)?;
+        for c in Controller::ALL {
+            self.cmd_with_data(c, Command::DeepSleep, &[0xA5])?;
+        }
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    /// Sets the temperature (in °C) forced onto all controllers
+    ///
+    /// The waveforms depend on it. It is applied immediately and kept over
+    /// [`wake_up`](Self::wake_up).
+    pub fn set_temperature(&mut self, celsius: i8) -> Result<(), SPI::Error> {
+        self.temperature = celsius;
+        self.send_temperature()
+    }
+
+    /// Transmits a full frame to the SRAM of all four controllers
+    ///
+    /// `buffer` covers the whole panel, e.g. a [`Display12in48`] buffer. Every
+    /// controller gets its quadrant of it.
+    ///
+    /// # Panics
+    ///
+    /// If `buffer` is shorter than `WIDTH / 8 * HEIGHT` bytes.
+    pub fn update_frame(&mut self, buffer: &[u8], delay: &mut DELAY) -> Result<(), SPI::Error> {
+        assert!(
+            buffer.len() >= LINE_BYTES * HEIGHT as usize,
+            "buffer too short for the 12in48"
+        );
+        self.wait_until_idle(delay)?;
+
+        for c in Controller::ALL {
+            let (columns, lines) = c.quadrant();
+            self.command(c, Command::DataStartTransmission2)?;
+            for line in lines {
+                let start = line * LINE_BYTES;
+                let data = &buffer[start + columns.start..start + columns.end];
+                self.interface.data(&mut self.spi[c as usize], data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Refreshes all four quadrants together
+    pub fn display_frame(&mut self, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // power is provided by the masters
+        for c in [Controller::M1, Controller::M2] {
+            self.command(c, Command::PowerOn)?;
+        }
+        delay.delay_us(300_000);
+
+        for c in Controller::ALL {
+            self.command(c, Command::DisplayRefresh)?;
+        }
+        delay.delay_us(100_000);
+        self.wait_until_idle(delay)
+    }
+
+    /// Provide a combined update&display and save some time (skipping a busy check in between)
+    ///
+    /// # Panics
+    ///
+    /// If `buffer` is too short, see [`update_frame`](Self::update_frame).
+    pub fn update_and_display_frame(
+        &mut self,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(buffer, delay)?;
+        self.display_frame(delay)
+    }
+
+    /// Clears the whole panel with the background color and refreshes it
+    pub fn clear_frame(&mut self, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let bg = self.color.get_byte_value();
+
+        self.wait_until_idle(delay)?;
+        for c in Controller::ALL {
+            let (columns, lines) = c.quadrant();
+            let len = (columns.len() * lines.len()) as u32;
+            for command in [
+                Command::DataStartTransmission1,
+                Command::DataStartTransmission2,
+            ] {
+                self.command(c, command)?;
+                self.interface
+                    .data_x_times(&mut self.spi[c as usize], bg, len)?;
+            }
+        }
+
+        self.display_frame(delay)
+    }
+
+    /// Sets the backgroundcolor for various commands like [clear_frame](Self::clear_frame)
+    pub fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    /// Get current background color
+    pub fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    /// Get the width of the display
+    pub fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    /// Get the height of the display
+    pub fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    /// Wait until all four controllers have stopped processing data
+    ///
+    /// A [busy wait](crate::traits::DisplaySettings::set_busy_wait) gets the BUSY pin of
+    /// the controller that is still busy.
+    pub fn wait_until_idle(&mut self, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        for c in Controller::ALL {
+            let mut polls = 0;
+            loop {
+                self.command(c, Command::GetStatus)?;
+                let busy = match c {
+                    Controller::M1 => self.interface.is_busy(IS_BUSY_LOW),
+                    _ => self.busy[c as usize - 1].is_low().unwrap_or(false),
+                };
+                if !busy {
+                    break;
+                }
+                match c {
+                    Controller::M1 => self.interface.busy_pause(delay, IS_BUSY_LOW),
+                    _ => self.interface.busy_pause_on(
+                        &mut self.busy[c as usize - 1],
+                        delay,
+                        IS_BUSY_LOW,
+                    ),
+                }
+                polls += 1;
+            }
+            self.interface.trace_busy(polls);
+        }
+        Ok(())
+    }
+
+    fn send_temperature(&mut self) -> Result<(), SPI::Error> {
+        for c in Controller::ALL {
+            self.cmd_with_data(c, Command::CascadeSetting, &[0x03])?;
+            self.cmd_with_data(c, Command::ForceTemperature, &[self.temperature as u8])?;
+        }
+        Ok(())
+    }
+
+    fn command(&mut self, controller: Controller, command: Command) -> Result<(), SPI::Error> {
+        self.interface
+            .trace(TraceEvent::Controller(controller.name()));
+        self.interface
+            .cmd(&mut self.spi[controller as usize], command)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        controller: Controller,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.interface
+            .trace(TraceEvent::Controller(controller.name()));
+        self.interface
+            .cmd_with_data(&mut self.spi[controller as usize], command, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 1304);
+        assert_eq!(HEIGHT, 984);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+
+    #[test]
+    fn quadrants_match_c_driver() {
+        // EPD_12in48_Display sends the lower half to M1 and S1, the upper one to M2 and S2
+        assert_eq!(Controller::M1.quadrant(), (0..81, 492..984));
+        assert_eq!(Controller::S1.quadrant(), (81..163, 492..984));
+        assert_eq!(Controller::M2.quadrant(), (81..163, 0..492));
+        assert_eq!(Controller::S2.quadrant(), (0..81, 0..492));
+        assert_eq!(Controller::M2.panel_setting(), 0x13);
+        assert_eq!(Controller::M1.panel_setting(), 0x1F);
+    }
+}
@@ -0,0 +1,78 @@
+//! SPI Commands for the Waveshare 13.3" (E) Spectra 6 E-Ink Display
+
+use crate::traits;
+
+/// Epd13in3e commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// The names follow the Waveshare C driver, most of these commands are not documented
+/// in a public datasheet.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Panel Setting (PSR)
+    PanelSetting = 0x00,
+    /// Power Setting (PWR)
+    PowerSetting = 0x01,
+    /// Power Off (POF)
+    PowerOff = 0x02,
+    /// Power On (PON)
+    PowerOn = 0x04,
+    /// Booster soft start for the negative voltages (BTST_N)
+    BoosterSoftStartN = 0x05,
+    /// Booster soft start for the positive voltages (BTST_P)
+    BoosterSoftStartP = 0x06,
+    /// Deep Sleep (DSLP)
+    ///
+    /// The only one parameter is a check code, the command would be excuted if check code = 0xA5.
+    DeepSleep = 0x07,
+    /// Data Start Transmission (DTM)
+    DataStartTransmission = 0x10,
+    /// Display Refresh (DRF)
+    DisplayRefresh = 0x12,
+    /// VCOM and Data Interval Setting (CDI)
+    VcomAndDataIntervalSetting = 0x50,
+    /// TCON Setting (TCON)
+    TconSetting = 0x60,
+    /// Resolution Setting (TRES)
+    TconResolution = 0x61,
+    /// Analog timing (AN_TM)
+    AnalogTiming = 0x74,
+    /// Auto gate idle (AGID)
+    AutoGateIdle = 0x86,
+    /// Buck boost setting of VDDN (BUCK_BOOST_VDDN)
+    BuckBoostVddn = 0xB0,
+    /// TFT VCOM power (TFT_VCOM_POWER)
+    TftVcomPower = 0xB1,
+    /// Enable buffer (EN_BUF)
+    EnableBuffer = 0xB6,
+    /// Enable boost of VDDP (BOOST_VDDP_EN)
+    BoostVddpEnable = 0xB7,
+    /// Cascade Setting (CCSET)
+    CascadeSetting = 0xE0,
+    /// Power Saving (PWS)
+    PowerSaving = 0xE3,
+    /// Unlocks the manufacturer settings (CMD66)
+    Cmd66 = 0xF0,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::PanelSetting.address(), 0x00);
+        assert_eq!(Command::DisplayRefresh.address(), 0x12);
+        assert_eq!(Command::Cmd66.address(), 0xF0);
+    }
+}
@@ -0,0 +1,345 @@
+//! A simple Driver for the Waveshare 13.3inch e-Paper HAT+ (E) Spectra 6 Display via SPI
+//!
+//! The panel is driven by two cascaded controllers. The left half of every row belongs
+//! to the main controller (`CS_M`), the right half to the secondary one (`CS_S`).
+//! Both chip selects are owned by the driver, so the [`SpiDevice`] handed in must not
+//! toggle a chip select of its own (e.g. an `ExclusiveDevice` built with `NoCs`).
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/13.3inch_e-Paper_HAT+_(E))
+//! - [Waveshare C driver](https://github.com/waveshareteam/e-Paper/blob/master/E-paper_Separate_Program/13.3inch_e-Paper_E/RaspberryPi/c/lib/e-Paper/EPD_13in3e.c)
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::color::HexColor;
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+
+pub(crate) mod command;
+use self::command::Command;
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 13in3e EPD
+#[cfg(feature = "graphics")]
+pub type Display13in3e = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize, HEIGHT as usize * 4) },
+    HexColor,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 1200;
+/// Height of the display
+pub const HEIGHT: u32 = 1600;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: HexColor = HexColor::White;
+const IS_BUSY_LOW: bool = true;
+const RESET: ResetProfile = ResetProfile::new(20_000, 2_000);
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// Bytes of one full row (two pixels per byte)
+const ROW_BYTES: usize = WIDTH as usize / 2;
+/// Bytes of one row handled by a single controller
+const HALF_ROW_BYTES: usize = ROW_BYTES / 2;
+
+/// Controller(s) a command is addressed to
+#[derive(Clone, Copy)]
+enum Chip {
+    /// Main controller, left half of the panel
+    Main,
+    /// Secondary controller, right half of the panel
+    Secondary,
+    /// Both controllers at once
+    Both,
+}
+
+/// Epd13in3e driver
+///
+/// Unlike the other drivers this one doesn't implement
+/// [`WaveshareDisplay`](crate::traits::WaveshareDisplay), as it needs the two
+/// chip select pins. The inherent methods mirror that trait instead,
+/// [`DisplaySettings`](crate::traits::DisplaySettings) is implemented as usual.
+pub struct Epd13in3e<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Chip select of the main controller
+    cs_m: CS,
+    /// Chip select of the secondary controller
+    cs_s: CS,
+    /// Background Color
+    color: HexColor,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST>
+    for Epd13in3e<SPI, CS, BUSY, DC, RST, DELAY>
+{
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd13in3e<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Creates a new driver from a SPI peripheral, the two chip selects, BUSY InputPin,
+    /// DC and RST
+    ///
+    /// This already initialises the device.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spi: &mut SPI,
+        cs_m: CS,
+        cs_s: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let interface = DisplayInterface::new(busy, dc, rst, delay_us);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Epd13in3e {
+            interface,
+            cs_m,
+            cs_s,
+            color,
+        };
+        epd.deselect();
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Reset the device
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
+        self.wait_until_idle(spi, delay)?;
+
+        self.cmd_with_data(
+            spi,
+            Chip::Main,
+            Command::AnalogTiming,
+            &[0xC0, 0x1C, 0x1C, 0xCC, 0xCC, 0xCC, 0x15, 0x15, 0x55],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Chip::Both,
+            Command::Cmd66,
+            &[0x49, 0x55, 0x13, 0x5D, 0x05, 0x10],
+        )?;
+        self.cmd_with_data(spi, Chip::Both, Command::PanelSetting, &[0xDF, 0x69])?;
+        self.cmd_with_data(
+            spi,
+            Chip::Both,
+            Command::VcomAndDataIntervalSetting,
+            &[0xF7],
+        )?;
+        self.cmd_with_data(spi, Chip::Both, Command::TconSetting, &[0x03, 0x03])?;
+        self.cmd_with_data(spi, Chip::Both, Command::AutoGateIdle, &[0x10])?;
+        self.cmd_with_data(spi, Chip::Both, Command::PowerSaving, &[0x22])?;
+        self.cmd_with_data(spi, Chip::Both, Command::CascadeSetting, &[0x01])?;
+        self.send_resolution(spi)?;
+
+        self.cmd_with_data(
+            spi,
+            Chip::Main,
+            Command::PowerSetting,
+            &[0x0F, 0x00, 0x28, 0x2C, 0x28, 0x38],
+        )?;
+        self.cmd_with_data(spi, Chip::Main, Command::EnableBuffer, &[0x07])?;
+        self.cmd_with_data(spi, Chip::Main, Command::BoosterSoftStartP, &[0xD8, 0x18])?;
+        self.cmd_with_data(spi, Chip::Main, Command::BoostVddpEnable, &[0x01])?;
+        self.cmd_with_data(spi, Chip::Main, Command::BoosterSoftStartN, &[0xD8, 0x18])?;
+        self.cmd_with_data(spi, Chip::Main, Command::BuckBoostVddn, &[0x01])?;
+        self.cmd_with_data(spi, Chip::Main, Command::TftVcomPower, &[0x02])?;
+        Ok(())
+    }
+
+    /// Wakes the device up and re-initialises it
+    pub fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(spi, delay)
+    }
+
+    /// Puts both controllers into deep sleep
+    ///
+    /// Only a reset (e.g. [`wake_up`](Self::wake_up)) brings them back.
+    pub fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.cmd_with_data(spi, Chip::Both, Command::DeepSleep, &[0xA5])?;
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    /// Transmits a full frame to the SRAM of both controllers
+    ///
+    /// `buffer` holds two pixels per byte, row by row, as provided by
+    /// [`Display13in3e`].
+    pub fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+
+        for (chip, offset) in [(Chip::Main, 0), (Chip::Secondary, HALF_ROW_BYTES)] {
+            self.select(chip);
+            self.interface.cmd(spi, Command::DataStartTransmission)?;
+            for row in buffer.chunks(ROW_BYTES) {
+                let half = row.get(offset..).unwrap_or(&[]);
+                self.interface
+                    .data(spi, &half[..half.len().min(HALF_ROW_BYTES)])?;
+            }
+            self.deselect();
+        }
+        Ok(())
+    }
+
+    /// Displays the frame data from SRAM
+    pub fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.command(spi, Chip::Both, Command::PowerOn)?;
+        self.wait_until_idle(spi, delay)?;
+        delay.delay_us(50_000);
+
+        self.cmd_with_data(spi, Chip::Both, Command::DisplayRefresh, &[0x00])?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.cmd_with_data(spi, Chip::Both, Command::PowerOff, &[0x00])?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    /// Provide a combined update&display and save some time (skipping a busy check in between)
+    pub fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    /// Clears the whole panel with the background color and refreshes it
+    pub fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let bg = HexColor::colors_byte(self.color, self.color);
+
+        self.wait_until_idle(spi, delay)?;
+        for chip in [Chip::Main, Chip::Secondary] {
+            self.select(chip);
+            self.interface.cmd(spi, Command::DataStartTransmission)?;
+            self.interface
+                .data_x_times(spi, bg, (HALF_ROW_BYTES * HEIGHT as usize) as u32)?;
+            self.deselect();
+        }
+
+        self.display_frame(spi, delay)
+    }
+
+    /// Sets the backgroundcolor for various commands like [clear_frame](Self::clear_frame)
+    pub fn set_background_color(&mut self, color: HexColor) {
+        self.color = color;
+    }
+
+    /// Get current background color
+    pub fn background_color(&self) -> &HexColor {
+        &self.color
+    }
+
+    /// Get the width of the display
+    pub fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    /// Get the height of the display
+    pub fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    /// Wait until the display has stopped processing data
+    pub fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        Ok(())
+    }
+
+    fn select(&mut self, chip: Chip) {
+        match chip {
+            Chip::Main => {
+                let _ = self.cs_m.set_low();
+            }
+            Chip::Secondary => {
+                let _ = self.cs_s.set_low();
+            }
+            Chip::Both => {
+                let _ = self.cs_m.set_low();
+                let _ = self.cs_s.set_low();
+            }
+        }
+    }
+
+    fn deselect(&mut self) {
+        let _ = self.cs_m.set_high();
+        let _ = self.cs_s.set_high();
+    }
+
+    fn command(&mut self, spi: &mut SPI, chip: Chip, command: Command) -> Result<(), SPI::Error> {
+        self.select(chip);
+        let res = self.interface.cmd(spi, command);
+        self.deselect();
+        res
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        chip: Chip,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.select(chip);
+        let res = self.interface.cmd_with_data(spi, command, data);
+        self.deselect();
+        res
+    }
+
+    fn send_resolution(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        // Each controller owns half of the panel, the cascade expects the
+        // resolution in its own (rotated) orientation: 1200 x 800
+        let w = self.width();
+        let h = self.height() / 2;
+
+        self.cmd_with_data(
+            spi,
+            Chip::Both,
+            Command::TconResolution,
+            &[(w >> 8) as u8, w as u8, (h >> 8) as u8, h as u8],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 1200);
+        assert_eq!(HEIGHT, 1600);
+        assert_eq!(HALF_ROW_BYTES, 300);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, HexColor::White);
+    }
+}
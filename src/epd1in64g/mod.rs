@@ -0,0 +1,78 @@
+//! A simple Driver for the Waveshare 1.64" (G) E-Ink Display via SPI
+//!
+//! The panel shows black, white, yellow and red, drawn with [`QuadColor`] on a
+//! `Display1in64g`, whose buffer is sent to the controller unchanged. The driver is
+//! [`EpdG`] with the [`Panel1in64g`] settings.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/1.64inch_e-Paper_Module_(G))
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_1in64g.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd1in64g.py)
+
+use crate::color::QuadColor;
+use crate::interface::ResetProfile;
+use crate::type_g::{command::Command, EpdG, GPanel, Panel, Sequence};
+
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 1in64g EPD
+#[cfg(feature = "graphics")]
+pub type Display1in64g = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize * 2, HEIGHT as usize) },
+    QuadColor,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 168;
+/// Height of the display
+pub const HEIGHT: u32 = 168;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: QuadColor = QuadColor::White;
+const RESET: ResetProfile = ResetProfile::new(20_000, 2_000);
+
+/// Initialisation from the Waveshare sample code
+const INIT_SEQUENCE: &Sequence = &[
+    (Command::CmdhSmall, &[0x49, 0x55, 0x13, 0x5D, 0x05, 0x10]),
+    (Command::ManufacturerB0, &[0x00]),
+    (Command::PowerSetting, &[0x0F, 0x00]),
+    (Command::PanelSetting, &[0x4F, 0x6B]),
+    (Command::BoosterSoftStart2, &[0xD7, 0xDE, 0x12]),
+    (Command::TconResolution, &[0x00, 0xA8, 0x00, 0xA8]),
+    (Command::VcomAndDataIntervalSetting, &[0x37]),
+    (Command::TconSetting, &[0x0C, 0x05]),
+    (Command::PowerSaving, &[0xFF]),
+    (Command::VcomDcPowerOff, &[0x00]),
+];
+
+/// The 1in64g panel for [`EpdG`]
+#[derive(Clone, Copy, Debug)]
+pub struct Panel1in64g;
+
+impl GPanel for Panel1in64g {
+    const PANEL: Panel = Panel {
+        width: WIDTH,
+        height: HEIGHT,
+        reset: RESET,
+        init: INIT_SEQUENCE,
+    };
+}
+
+/// Epd1in64g driver
+pub type Epd1in64g<SPI, BUSY, DC, RST, DELAY> = EpdG<Panel1in64g, SPI, BUSY, DC, RST, DELAY>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 168);
+        assert_eq!(HEIGHT, 168);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, QuadColor::White);
+    }
+}
@@ -0,0 +1,66 @@
+//! SPI Commands for the Waveshare 2.66" (B) E-Ink Display
+
+use crate::traits;
+
+/// Epd2in66b commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// For more infos about the addresses and what they are doing look into the PDFs.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Gate setting, number of lines and scanning order
+    DriverOutputControl = 0x01,
+    /// Deep sleep mode
+    ///
+    /// 0x01: mode 1, RAM is retained
+    DeepSleepMode = 0x10,
+    /// Define data entry sequence, X/Y increment or decrement
+    DataEntryModeSetting = 0x11,
+    /// Resets the commands and parameters to their S/W Reset default values except R10h-Deep Sleep Mode.
+    /// During operation, BUSY pad will output high.
+    SwReset = 0x12,
+    /// Selects the internal or an external temperature sensor
+    TemperatureSensorControl = 0x18,
+    /// Activate display update sequence
+    MasterActivation = 0x20,
+    /// RAM content options (normal, bypass or inverse) and source output mode
+    DisplayUpdateControl1 = 0x21,
+    /// Display update sequence option
+    DisplayUpdateControl2 = 0x22,
+    /// After this command, data entries will be written into the black/white RAM until another command is written
+    WriteBlackWhiteRam = 0x24,
+    /// After this command, data entries will be written into the red RAM until another command is written
+    WriteRedRam = 0x26,
+    /// Select border waveform for VBD
+    BorderWaveformControl = 0x3C,
+    /// Specify the start/end positions of the window address in the X direction
+    SetRamXAddressStartEndPosition = 0x44,
+    /// Specify the start/end positions of the window address in the Y direction
+    SetRamYAddressStartEndPosition = 0x45,
+    /// Make initial settings for the RAM X address in the address counter (AC)
+    SetRamXAddressCounter = 0x4E,
+    /// Make initial settings for the RAM Y address in the address counter (AC)
+    SetRamYAddressCounter = 0x4F,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::DriverOutputControl.address(), 0x01);
+        assert_eq!(Command::WriteRedRam.address(), 0x26);
+        assert_eq!(Command::SetRamYAddressCounter.address(), 0x4F);
+    }
+}
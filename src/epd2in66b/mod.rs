@@ -0,0 +1,384 @@
+//! A simple Driver for the Waveshare 2.66" (B) E-Ink Display via SPI
+//!
+//! This is the black/white/red panel of the Pico-ePaper-2.66-B module.
+//!
+//! # Chromatic plane
+//!
+//! The red RAM of this controller shows a pixel red when its bit is set and takes
+//! precedence over the black/white RAM. That is the opposite polarity of the red
+//! image in Waveshare's sample code, which therefore inverts it before sending. The
+//! chromatic buffer of [`Display2in66b`] already has a set bit for every
+//! [`TriColor::Chromatic`] pixel and is sent unchanged. With `BWRBIT = true` those
+//! pixels keep a cleared (black) bit in the black/white plane, so a black/white only
+//! refresh still shows them.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/Pico-ePaper-2.66-B)
+//! - [Waveshare C driver](https://github.com/waveshare/Pico_ePaper_Code/blob/main/c/lib/e-Paper/EPD_2in66b.c)
+//! - [Waveshare MicroPython driver](https://github.com/waveshare/Pico_ePaper_Code/blob/main/python/Pico-ePaper-2.66-B.py)
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::color::TriColor;
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+use crate::traits::{
+    InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+};
+
+pub(crate) mod command;
+use self::command::Command;
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 2in66b EPD
+#[cfg(feature = "graphics")]
+pub type Display2in66b = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    true,
+    { buffer_len(WIDTH as usize, HEIGHT as usize) * 2 },
+    TriColor,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 152;
+/// Height of the display
+pub const HEIGHT: u32 = 296;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: TriColor = TriColor::White;
+const IS_BUSY_LOW: bool = false;
+/// Reset pulse, the controller signals the end of its reset on BUSY
+const RESET: ResetProfile = ResetProfile::new(200_000, 2_000).with_busy_wait(10_000);
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// Bytes of a single color plane
+const NUM_BYTES: u32 = WIDTH * HEIGHT / 8;
+
+/// Epd2in66b driver
+///
+pub struct Epd2in66b<SPI, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Background Color
+    color: TriColor,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in66b<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Reset the device
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
+
+        self.command(spi, Command::SwReset)?;
+        self.wait_until_idle(spi, delay)?;
+
+        // X increment, Y increment
+        self.cmd_with_data(spi, Command::DataEntryModeSetting, &[0x03])?;
+        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+        // both RAMs normal, source output from S8 to S167
+        self.cmd_with_data(spi, Command::DisplayUpdateControl1, &[0x00, 0x80])?;
+        self.set_ram_counter(spi, 0, 0)?;
+        self.wait_until_idle(spi, delay)
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in66b<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn update_color_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        black: &[u8],
+        chromatic: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.update_achromatic_frame(spi, delay, black)?;
+        self.update_chromatic_frame(spi, delay, chromatic)
+    }
+
+    /// Update only the black/white data of the display.
+    ///
+    /// A set bit is a white pixel, a cleared bit a black one.
+    fn update_achromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        black: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_counter(spi, 0, 0)?;
+        self.cmd_with_data(spi, Command::WriteBlackWhiteRam, black)
+    }
+
+    /// Update only the chromatic data of the display.
+    ///
+    /// Every set bit is drawn red.
+    fn update_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        chromatic: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_counter(spi, 0, 0)?;
+        self.cmd_with_data(spi, Command::WriteRedRam, chromatic)
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in66b<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = TriColor;
+
+    fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let interface = DisplayInterface::new(busy, dc, rst, delay_us);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Epd2in66b { interface, color };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    /// Transmits the black/white data and clears the chromatic plane
+    ///
+    /// Use [`WaveshareThreeColorDisplay::update_color_frame`] to send both planes.
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_achromatic_frame(spi, delay, buffer)?;
+        self.set_ram_counter(spi, 0, 0)?;
+        self.command(spi, Command::WriteRedRam)?;
+        self.interface.data_x_times(spi, 0x00, NUM_BYTES)
+    }
+
+    /// The controller has no partial window: a window covering the whole display is sent
+    /// like [`update_frame`](WaveshareDisplay::update_frame), other windows are ignored
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        if (x, y, width, height) == (0, 0, WIDTH, HEIGHT) {
+            self.update_frame(spi, buffer, delay)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let (bw, chromatic) = match self.color {
+            TriColor::Black => (0x00, 0x00),
+            TriColor::White => (0xFF, 0x00),
+            TriColor::Chromatic => (0x00, 0xFF),
+        };
+
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_counter(spi, 0, 0)?;
+        self.command(spi, Command::WriteBlackWhiteRam)?;
+        self.interface.data_x_times(spi, bw, NUM_BYTES)?;
+
+        self.set_ram_counter(spi, 0, 0)?;
+        self.command(spi, Command::WriteRedRam)?;
+        self.interface.data_x_times(spi, chromatic, NUM_BYTES)?;
+
+        self.display_frame(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: TriColor) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &TriColor {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    /// The display has a single waveform, there is no LUT to switch to
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), SPI::Error> {
+        Ok(())
+    }
+
+    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST> for Epd2in66b<SPI, BUSY, DC, RST, DELAY> {
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> Epd2in66b<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
+        self.interface.cmd(spi, command)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.interface.cmd_with_data(spi, command, data)
+    }
+
+    fn set_ram_area(
+        &mut self,
+        spi: &mut SPI,
+        start_x: u32,
+        start_y: u32,
+        end_x: u32,
+        end_y: u32,
+    ) -> Result<(), SPI::Error> {
+        // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
+        // aren't relevant
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[(start_x >> 3) as u8 & 0x1F, (end_x >> 3) as u8 & 0x1F],
+        )?;
+
+        // 2 Databytes: A[7:0] & 0..A[8] for each - start and end
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[
+                start_y as u8,
+                (start_y >> 8) as u8 & 0x01,
+                end_y as u8,
+                (end_y >> 8) as u8 & 0x01,
+            ],
+        )
+    }
+
+    fn set_ram_counter(&mut self, spi: &mut SPI, x: u32, y: u32) -> Result<(), SPI::Error> {
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressCounter,
+            &[(x >> 3) as u8 & 0x1F],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8 & 0x01],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 152);
+        assert_eq!(HEIGHT, 296);
+        assert_eq!(NUM_BYTES, 5_624);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, TriColor::White);
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn chromatic_pixel_planes() {
+        use embedded_graphics::prelude::*;
+
+        let mut display = Display2in66b::default();
+        display.clear(TriColor::White).ok();
+        Pixel(Point::new(1, 0), TriColor::Chromatic)
+            .draw(&mut display)
+            .ok();
+
+        // black underneath, red on top
+        assert_eq!(display.bw_buffer()[0], 0b1011_1111);
+        assert_eq!(display.chromatic_buffer()[0], 0b0100_0000);
+    }
+}
@@ -0,0 +1,37 @@
+//! Look-Up Table of the 2.7" V2 E-Ink Display
+//!
+//! Taken from the Waveshare sample code. The normal and the fast refresh use waveforms
+//! stored in the OTP of the controller.
+
+/// 4-grey refresh
+///
+/// 12 groups of voltage settings, 12 groups of phase timings and the frame rates.
+pub(crate) const LUT_GRAY4: [u8; 153] = [
+    0x40, 0x48, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L0
+    0x08, 0x48, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L1
+    0x02, 0x48, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L2
+    0x20, 0x48, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L3
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L4
+    0x0A, 0x19, 0x00, 0x03, 0x08, 0x00, 0x00, // TP, SR, RP of group 0
+    0x14, 0x01, 0x00, 0x14, 0x01, 0x00, 0x03, // 1
+    0x0A, 0x03, 0x00, 0x08, 0x19, 0x00, 0x00, // 2
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, // 3
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 4
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 5
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 6
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 7
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 8
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 9
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 10
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 11
+    0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x00, 0x00, 0x00, // FR, XON
+];
+
+/// EOPT byte following [`LUT_GRAY4`]
+pub(crate) const LUT_GRAY4_END: u8 = 0x22;
+/// VGH of the 4-grey refresh
+pub(crate) const GRAY4_GATE_VOLTAGE: u8 = 0x17;
+/// VSH1, VSH2 and VSL of the 4-grey refresh
+pub(crate) const GRAY4_SOURCE_VOLTAGE: [u8; 3] = [0x41, 0x00, 0x32];
+/// VCOM of the 4-grey refresh
+pub(crate) const GRAY4_VCOM: u8 = 0x1C;
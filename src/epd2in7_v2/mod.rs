@@ -0,0 +1,534 @@
+//! A simple Driver for the Waveshare 2.7" V2 E-Ink Display via SPI
+//!
+//! The panel is 176 pixels wide and 264 pixels high and uses a SSD1680 controller.
+//!
+//! # Refresh modes
+//!
+//! The mode is selected with [`WaveshareDisplay::set_lut`], which reinitialises the
+//! controller:
+//!
+//! - [`RefreshLut::Full`] is the normal refresh with the waveform from the OTP
+//! - [`RefreshLut::Quick`] uses the faster OTP waveform of a higher temperature
+//! - [`RefreshLut::Gray4`] shows four levels of grey. The frame buffers then hold
+//!   2 bits per pixel, as drawn with [`Gray4Color`] on a `Display2in7Gray4`.
+//!
+//! # Keys
+//!
+//! The HAT has four keys next to the display. They pull their pins low while pressed
+//! and can be handed to [`Epd2in7Hat::new_with_keys`], which makes the whole HAT one
+//! device. The keys have a pin type of their own. [`Epd2in7`] is the driver without them.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/2.7inch_e-Paper_HAT_Manual)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_2in7_V2.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd2in7_V2.py)
+
+use core::convert::Infallible;
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{ErrorType, InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::buffer_len;
+use crate::color::{Color, Gray4Color};
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+use crate::traits::{InternalWiAdditions, RefreshLut, WaveshareDisplay};
+
+pub(crate) mod command;
+use self::command::Command;
+pub(crate) mod constants;
+use self::constants::{
+    GRAY4_GATE_VOLTAGE, GRAY4_SOURCE_VOLTAGE, GRAY4_VCOM, LUT_GRAY4, LUT_GRAY4_END,
+};
+
+/// Full size buffer for use with the 2in7 V2 EPD
+#[cfg(feature = "graphics")]
+pub type Display2in7 = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize, HEIGHT as usize) },
+    Color,
+>;
+
+/// Full size buffer for the 4-grey mode, see [`RefreshLut::Gray4`]
+#[cfg(feature = "graphics")]
+pub type Display2in7Gray4 =
+    crate::graphics::Display<WIDTH, HEIGHT, false, GRAY4_BUFFER_LEN, Gray4Color>;
+
+/// Width of the display
+pub const WIDTH: u32 = 176;
+/// Height of the display
+pub const HEIGHT: u32 = 264;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+/// Length of a full frame in 4-grey mode, 2 bits per pixel
+pub const GRAY4_BUFFER_LEN: usize = buffer_len(WIDTH as usize * 2, HEIGHT as usize);
+const IS_BUSY_LOW: bool = false;
+/// Reset pulse, the controller signals the end of its reset on BUSY
+const RESET: ResetProfile = ResetProfile::new(10_000, 2_000).with_busy_wait(10_000);
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// Bytes of a display line in one RAM
+const LINE_BYTES: usize = WIDTH as usize / 8;
+
+/// The keys of the 2.7" HAT, from top to bottom
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    /// KEY1, BCM pin 5 on the HAT
+    Key1,
+    /// KEY2, BCM pin 6 on the HAT
+    Key2,
+    /// KEY3, BCM pin 13 on the HAT
+    Key3,
+    /// KEY4, BCM pin 19 on the HAT
+    Key4,
+}
+
+/// Key pin type of [`Epd2in7`], which has no keys
+#[derive(Debug)]
+pub enum NoKey {}
+
+impl ErrorType for NoKey {
+    type Error = Infallible;
+}
+
+impl InputPin for NoKey {
+    fn is_high(&mut self) -> Result<bool, Infallible> {
+        match *self {}
+    }
+
+    fn is_low(&mut self) -> Result<bool, Infallible> {
+        match *self {}
+    }
+}
+
+/// Epd2in7 (V2) driver without the keys of the HAT
+pub type Epd2in7<SPI, BUSY, DC, RST, DELAY> = Epd2in7Hat<SPI, BUSY, DC, RST, DELAY, NoKey>;
+
+/// Epd2in7 (V2) driver, owning the four keys of the HAT if created with
+/// [`new_with_keys`](Epd2in7Hat::new_with_keys)
+///
+pub struct Epd2in7Hat<SPI, BUSY, DC, RST, DELAY, KEY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Background Color
+    color: Color,
+    /// Refresh LUT
+    refresh: RefreshLut,
+    /// The keys of the HAT, in the order of [`Key`]
+    keys: Option<[KEY; 4]>,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY, KEY> InternalWiAdditions<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in7Hat<SPI, BUSY, DC, RST, DELAY, KEY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Reset the device
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
+
+        self.command(spi, Command::SwReset)?;
+        self.wait_until_idle(spi, delay)?;
+
+        if self.refresh == RefreshLut::Gray4 {
+            self.cmd_with_data(spi, Command::AnalogBlockControl, &[0x54])?;
+            self.cmd_with_data(spi, Command::DigitalBlockControl, &[0x3B])?;
+        }
+
+        self.cmd_with_data(
+            spi,
+            Command::DriverOutputControl,
+            &[(HEIGHT - 1) as u8, ((HEIGHT - 1) >> 8) as u8, 0x00],
+        )?;
+        // X increment, Y increment
+        self.cmd_with_data(spi, Command::DataEntryModeSetting, &[0x03])?;
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
+
+        match self.refresh {
+            RefreshLut::Full => (),
+            RefreshLut::Quick => {
+                self.cmd_with_data(spi, Command::TemperatureSensorControl, &[0x80])?;
+                // load the temperature and the waveform
+                self.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xB1])?;
+                self.command(spi, Command::MasterActivation)?;
+                self.wait_until_idle(spi, delay)?;
+
+                // pretend a higher temperature, which selects the fast waveform
+                self.cmd_with_data(spi, Command::WriteTemperatureRegister, &[0x64, 0x00])?;
+                self.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0x91])?;
+                self.command(spi, Command::MasterActivation)?;
+                self.wait_until_idle(spi, delay)?;
+            }
+            RefreshLut::Gray4 => {
+                self.cmd_with_data(spi, Command::BorderWaveformControl, &[0x00])?;
+                self.cmd_with_data(spi, Command::WriteVcomRegister, &[GRAY4_VCOM])?;
+                self.cmd_with_data(spi, Command::WriteLutRegisterEnd, &[LUT_GRAY4_END])?;
+                self.cmd_with_data(spi, Command::GateDrivingVoltage, &[GRAY4_GATE_VOLTAGE])?;
+                self.cmd_with_data(spi, Command::SourceDrivingVoltage, &GRAY4_SOURCE_VOLTAGE)?;
+                self.cmd_with_data(spi, Command::WriteLutRegister, &LUT_GRAY4)?;
+            }
+        }
+
+        self.set_ram_counter(spi, 0, 0)?;
+        self.wait_until_idle(spi, delay)
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY, KEY> WaveshareDisplay<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in7Hat<SPI, BUSY, DC, RST, DELAY, KEY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = Color;
+
+    fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let interface = DisplayInterface::new(busy, dc, rst, delay_us);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Epd2in7Hat {
+            interface,
+            color,
+            refresh: RefreshLut::Full,
+            keys: None,
+        };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    /// Transmits a full frame
+    ///
+    /// In [`RefreshLut::Gray4`] mode the buffer holds 2 bits per pixel and needs to be
+    /// [`GRAY4_BUFFER_LEN`] bytes long.
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_partial_frame(spi, delay, buffer, 0, 0, WIDTH, HEIGHT)
+    }
+
+    /// Transmits a part of the frame
+    ///
+    /// `x` and `width` have to be multiples of 8, as the RAM is addressed in whole bytes
+    /// in X direction. In [`RefreshLut::Gray4`] mode the buffer holds 2 bits per pixel,
+    /// 4 pixels per byte with the leftmost pixel in the highest bits.
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, x, y, width, height)?;
+
+        if self.refresh != RefreshLut::Gray4 {
+            self.set_ram_counter(spi, x, y)?;
+            return self.cmd_with_data(spi, Command::WriteRam, buffer);
+        }
+
+        // the high bit of every pixel goes to the BW RAM, the low bit to the second RAM
+        let row_len = width as usize / 4;
+        for (command, shift) in [(Command::WriteRam, 1), (Command::WriteRam2, 0)] {
+            self.set_ram_counter(spi, x, y)?;
+            self.command(spi, command)?;
+            let mut line = [0u8; LINE_BYTES];
+            for row in buffer.chunks(row_len) {
+                let len = gray4_plane_row(row, shift, &mut line);
+                self.interface.data(spi, &line[..len])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let sequence = match self.refresh {
+            RefreshLut::Full => 0xF7,
+            RefreshLut::Quick | RefreshLut::Gray4 => 0xC7,
+        };
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DisplayUpdateControl2, &[sequence])?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let bg = self.color.get_byte_value();
+
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
+        for command in [Command::WriteRam, Command::WriteRam2] {
+            self.set_ram_counter(spi, 0, 0)?;
+            self.command(spi, command)?;
+            self.interface.data_x_times(spi, bg, WIDTH * HEIGHT / 8)?;
+        }
+
+        self.display_frame(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), SPI::Error> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        self.init(spi, delay)
+    }
+
+    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY, KEY> HasSettings<BUSY, RST>
+    for Epd2in7Hat<SPI, BUSY, DC, RST, DELAY, KEY>
+{
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY, KEY> Epd2in7Hat<SPI, BUSY, DC, RST, DELAY, KEY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    KEY: InputPin,
+{
+    /// Creates a new driver together with the four keys of the HAT
+    ///
+    /// `keys` are in the order of [`Key`]. This already initialises the device.
+    pub fn new_with_keys(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        keys: [KEY; 4],
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let mut epd = Self::new(spi, busy, dc, rst, delay, delay_us)?;
+        epd.keys = Some(keys);
+        Ok(epd)
+    }
+
+    /// Returns the pin of a key, if the keys were passed to
+    /// [`new_with_keys`](Epd2in7Hat::new_with_keys)
+    pub fn key(&mut self, key: Key) -> Option<&mut KEY> {
+        self.keys.as_mut().map(|keys| &mut keys[key as usize])
+    }
+
+    /// Returns whether a key is pressed
+    ///
+    /// Always `false` if the driver was created without the keys.
+    pub fn is_key_pressed(&mut self, key: Key) -> Result<bool, KEY::Error> {
+        match self.key(key) {
+            Some(pin) => pin.is_low(),
+            None => Ok(false),
+        }
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY, KEY> Epd2in7Hat<SPI, BUSY, DC, RST, DELAY, KEY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
+        self.interface.cmd(spi, command)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.interface.cmd_with_data(spi, command, data)
+    }
+
+    fn set_ram_area(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        assert!(width > 0 && x + width <= WIDTH);
+        assert!(height > 0 && y + height <= HEIGHT);
+
+        let end_x = x + width - 1;
+        let end_y = y + height - 1;
+
+        // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
+        // aren't relevant
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[(x >> 3) as u8, (end_x >> 3) as u8],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[y as u8, (y >> 8) as u8, end_y as u8, (end_y >> 8) as u8],
+        )
+    }
+
+    fn set_ram_counter(&mut self, spi: &mut SPI, x: u32, y: u32) -> Result<(), SPI::Error> {
+        self.cmd_with_data(spi, Command::SetRamXAddressCounter, &[(x >> 3) as u8])?;
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8],
+        )
+    }
+}
+
+/// Packs one bit plane of a row of a 4-grey buffer, returns the number of bytes
+fn gray4_plane_row(row: &[u8], shift: u8, line: &mut [u8]) -> usize {
+    let len = (row.len() + 1) / 2;
+    for (byte, pixels) in line[..len].iter_mut().zip(row.chunks(2)) {
+        *byte = Gray4Color::plane_byte(pixels, shift);
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 176);
+        assert_eq!(HEIGHT, 264);
+        assert_eq!(GRAY4_BUFFER_LEN, 11_616);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+
+    #[test]
+    fn gray4_plane_split() {
+        let mut line = [0u8; LINE_BYTES];
+
+        // black, dark grey, light grey, white, repeated
+        let row = [0b0001_1011; WIDTH as usize / 4];
+        assert_eq!(gray4_plane_row(&row, 1, &mut line), LINE_BYTES);
+        assert!(line.iter().all(|&byte| byte == 0b0011_0011));
+        assert_eq!(gray4_plane_row(&row, 0, &mut line), LINE_BYTES);
+        assert!(line.iter().all(|&byte| byte == 0b0101_0101));
+
+        // a 16 pixel wide window
+        let mut line = [0u8; LINE_BYTES];
+        let row = [0xFF, 0x00, 0b1010_1010, 0b0101_0101];
+        assert_eq!(gray4_plane_row(&row, 1, &mut line), 2);
+        assert_eq!(line[..3], [0xF0, 0xF0, 0x00]);
+        assert_eq!(gray4_plane_row(&row, 0, &mut line), 2);
+        assert_eq!(line[..3], [0xF0, 0x0F, 0x00]);
+    }
+
+    #[test]
+    fn hat_keys() {
+        use embedded_hal_mock::eh1::{
+            delay::NoopDelay,
+            digital::{Mock, State, Transaction},
+            spi::Mock as SpiMock,
+        };
+
+        let mut pins = [
+            Mock::new(&[]),
+            Mock::new(&[Transaction::get(State::Low)]),
+            Mock::new(&[]),
+            Mock::new(&[Transaction::get(State::High)]),
+        ];
+        let mut interface_pins = [Mock::new(&[]), Mock::new(&[]), Mock::new(&[])];
+        let [busy, dc, rst] = interface_pins.clone();
+        let mut epd: Epd2in7Hat<SpiMock<u8>, _, _, _, NoopDelay, _> = Epd2in7Hat {
+            interface: DisplayInterface::new(busy, dc, rst, None),
+            color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            keys: Some(pins.clone()),
+        };
+        assert!(epd.is_key_pressed(Key::Key2).unwrap());
+        assert!(!epd.is_key_pressed(Key::Key4).unwrap());
+
+        epd.keys = None;
+        assert!(!epd.is_key_pressed(Key::Key1).unwrap());
+
+        for pin in pins.iter_mut().chain(interface_pins.iter_mut()) {
+            pin.done();
+        }
+    }
+}
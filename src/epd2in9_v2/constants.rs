@@ -0,0 +1,37 @@
+//! Look-Up Table of the 2.9" V2 E-Ink Display
+//!
+//! Taken from the Waveshare sample code. The full refresh uses the waveform stored in
+//! the OTP of the controller.
+
+/// Fast partial refresh
+///
+/// 12 groups of voltage settings, 12 groups of phase timings and the frame rates.
+pub(crate) const LUT_PARTIAL: [u8; 153] = [
+    0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L0
+    0x80, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L1
+    0x40, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L2
+    0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L3
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // VS L4
+    0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, // TP, SR, RP of group 0
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 1
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 2
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 3
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 4
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 5
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 6
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 7
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 8
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 9
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 10
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 11
+    0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x00, 0x00, 0x00, // FR, XON
+];
+
+/// EOPT byte following [`LUT_PARTIAL`]
+pub(crate) const LUT_PARTIAL_END: u8 = 0x22;
+/// VGH of the partial refresh
+pub(crate) const PARTIAL_GATE_VOLTAGE: u8 = 0x17;
+/// VSH1, VSH2 and VSL of the partial refresh
+pub(crate) const PARTIAL_SOURCE_VOLTAGE: [u8; 3] = [0x41, 0xB0, 0x32];
+/// VCOM of the partial refresh
+pub(crate) const PARTIAL_VCOM: u8 = 0x36;
@@ -0,0 +1,511 @@
+//! A simple Driver for the Waveshare 2.9" V2 E-Ink Display via SPI
+//!
+//! The V2 panel uses a SSD1680 controller. Its full refresh uses the waveform from the
+//! OTP, [`RefreshLut::Quick`] loads a fast partial refresh LUT from the host.
+//!
+//! # Partial refresh
+//!
+//! The partial waveform compares the new frame in the black/white RAM with the old frame
+//! in the second RAM, both of them can be written through [`QuickRefresh`]. With the
+//! ping-pong option set by the quick mode the controller copies the new frame into the
+//! second RAM after every refresh, so usually only the new frames have to be sent.
+//!
+//! Waking the panel up from deep sleep resets the controller. Use
+//! [`Epd2in9::restore_base_frame`] afterwards to load the image that is still shown
+//! back into both RAMs before continuing with partial refreshes.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/2.9inch_e-Paper_Module)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_2in9_V2.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd2in9_V2.py)
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::color::Color;
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+use crate::traits::{InternalWiAdditions, QuickRefresh, RefreshLut, WaveshareDisplay};
+
+pub(crate) mod command;
+use self::command::Command;
+pub(crate) mod constants;
+use self::constants::{
+    LUT_PARTIAL, LUT_PARTIAL_END, PARTIAL_GATE_VOLTAGE, PARTIAL_SOURCE_VOLTAGE, PARTIAL_VCOM,
+};
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 2in9 V2 EPD
+#[cfg(feature = "graphics")]
+pub type Display2in9 = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize, HEIGHT as usize) },
+    Color,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 128;
+/// Height of the display
+pub const HEIGHT: u32 = 296;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+const IS_BUSY_LOW: bool = false;
+/// Reset pulse, the controller signals the end of its reset on BUSY
+const RESET: ResetProfile = ResetProfile::new(10_000, 2_000).with_busy_wait(10_000);
+/// Reset pulse before loading the partial LUT, as short as in the Waveshare C driver
+const RESET_PARTIAL: ResetProfile = ResetProfile::new(1_000, 2_000).with_busy_wait(2_000);
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// Epd2in9 (V2) driver
+///
+pub struct Epd2in9<SPI, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Background Color
+    color: Color,
+    /// Refresh LUT
+    refresh: RefreshLut,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in9<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Reset the device
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
+
+        self.command(spi, Command::SwReset)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.cmd_with_data(
+            spi,
+            Command::DriverOutputControl,
+            &[(HEIGHT - 1) as u8, ((HEIGHT - 1) >> 8) as u8, 0x00],
+        )?;
+        // X increment, Y increment
+        self.cmd_with_data(spi, Command::DataEntryModeSetting, &[0x03])?;
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
+        self.cmd_with_data(spi, Command::DisplayUpdateControl1, &[0x00, 0x80])?;
+        self.set_ram_counter(spi, 0, 0)?;
+        self.wait_until_idle(spi, delay)?;
+
+        if self.refresh == RefreshLut::Quick {
+            self.set_partial_mode(spi, delay)?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in9<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = Color;
+
+    fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let interface = DisplayInterface::new(busy, dc, rst, delay_us);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Epd2in9 {
+            interface,
+            color,
+            refresh: RefreshLut::Full,
+        };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.write_ram(spi, delay, Command::WriteRam, buffer, 0, 0, WIDTH, HEIGHT)
+    }
+
+    /// Updates a part of the frame
+    ///
+    /// `x` and `width` have to be multiples of 8, as the RAM is addressed in whole bytes
+    /// in X direction.
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        self.write_ram(spi, delay, Command::WriteRam, buffer, x, y, width, height)
+    }
+
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let sequence = match self.refresh {
+            RefreshLut::Full | RefreshLut::Gray4 => 0xF7,
+            RefreshLut::Quick => 0x0F,
+        };
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DisplayUpdateControl2, &[sequence])?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.clear_partial_frame(spi, delay, 0, 0, WIDTH, HEIGHT)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    /// Switches between the full refresh from the OTP and the fast partial refresh
+    ///
+    /// Going back to [`RefreshLut::Full`] reinitialises the controller, which reloads
+    /// the OTP waveform.
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), SPI::Error> {
+        if let Some(refresh_lut) = refresh_rate {
+            if refresh_lut == self.refresh {
+                return Ok(());
+            }
+            self.refresh = refresh_lut;
+        }
+        match self.refresh {
+            RefreshLut::Full | RefreshLut::Gray4 => self.init(spi, delay),
+            RefreshLut::Quick => self.set_partial_mode(spi, delay),
+        }
+    }
+
+    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST> for Epd2in9<SPI, BUSY, DC, RST, DELAY> {
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> QuickRefresh<SPI, BUSY, DC, RST, DELAY>
+    for Epd2in9<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Writes the old frame into the second RAM
+    ///
+    /// Switches to the quick refresh if it isn't in use yet.
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.set_lut(spi, delay, Some(RefreshLut::Quick))?;
+        self.write_ram(spi, delay, Command::WriteRam2, buffer, 0, 0, WIDTH, HEIGHT)
+    }
+
+    /// Writes the new frame into the black/white RAM
+    ///
+    /// Switches to the quick refresh if it isn't in use yet.
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.set_lut(spi, delay, Some(RefreshLut::Quick))?;
+        self.write_ram(spi, delay, Command::WriteRam, buffer, 0, 0, WIDTH, HEIGHT)
+    }
+
+    /// This is a wrapper around `display_frame` for using this device as a true
+    /// `QuickRefresh` device.
+    fn display_new_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.display_frame(spi, delay)
+    }
+
+    /// This is wrapper around `update_new_frame` and `display_frame` for using
+    /// this device as a true `QuickRefresh` device.
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    /// `x` and `width` have to be multiples of 8.
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        self.set_lut(spi, delay, Some(RefreshLut::Quick))?;
+        self.write_ram(spi, delay, Command::WriteRam2, buffer, x, y, width, height)
+    }
+
+    /// `x` and `width` have to be multiples of 8.
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        self.set_lut(spi, delay, Some(RefreshLut::Quick))?;
+        self.write_ram(spi, delay, Command::WriteRam, buffer, x, y, width, height)
+    }
+
+    /// Fills the area with the background color in both RAMs
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        let bg = self.color.get_byte_value();
+
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, x, y, width, height)?;
+        for command in [Command::WriteRam, Command::WriteRam2] {
+            self.set_ram_counter(spi, x, y)?;
+            self.command(spi, command)?;
+            self.interface.data_x_times(spi, bg, width / 8 * height)?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> Epd2in9<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Writes the image that is currently shown into both RAMs, without a refresh
+    ///
+    /// The controller doesn't keep its RAM over [`WaveshareDisplay::wake_up`]. Call this
+    /// with the last displayed frame before the next partial refresh, otherwise it is
+    /// compared against an empty base image.
+    pub fn restore_base_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.write_ram(spi, delay, Command::WriteRam, buffer, 0, 0, WIDTH, HEIGHT)?;
+        self.write_ram(spi, delay, Command::WriteRam2, buffer, 0, 0, WIDTH, HEIGHT)
+    }
+
+    /// Shows `buffer` with a full refresh and makes it the base image for the following
+    /// partial refreshes
+    ///
+    /// The selected [`RefreshLut`] is kept.
+    pub fn update_and_display_base_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        let refresh = self.refresh;
+
+        self.set_lut(spi, delay, Some(RefreshLut::Full))?;
+        self.restore_base_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        self.set_lut(spi, delay, Some(refresh))
+    }
+
+    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
+        self.interface.cmd(spi, command)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.interface.cmd_with_data(spi, command, data)
+    }
+
+    /// Loads the partial LUT and enables the ping-pong mode of the RAMs
+    ///
+    /// Only pulses the reset line, a software reset would lose the RAM content.
+    fn set_partial_mode(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface.reset(delay, RESET_PARTIAL, IS_BUSY_LOW);
+
+        self.cmd_with_data(spi, Command::WriteLutRegister, &LUT_PARTIAL)?;
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::WriteLutRegisterEnd, &[LUT_PARTIAL_END])?;
+        self.cmd_with_data(spi, Command::GateDrivingVoltage, &[PARTIAL_GATE_VOLTAGE])?;
+        self.cmd_with_data(spi, Command::SourceDrivingVoltage, &PARTIAL_SOURCE_VOLTAGE)?;
+        self.cmd_with_data(spi, Command::WriteVcomRegister, &[PARTIAL_VCOM])?;
+
+        self.cmd_with_data(
+            spi,
+            Command::WriteDisplayOption,
+            &[0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00],
+        )?;
+        self.cmd_with_data(spi, Command::BorderWaveformControl, &[0x80])?;
+
+        // enable the analog and clock to apply the settings
+        self.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xC0])?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn write_ram(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        command: Command,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, x, y, width, height)?;
+        self.set_ram_counter(spi, x, y)?;
+        self.cmd_with_data(spi, command, buffer)
+    }
+
+    fn set_ram_area(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        assert!(width > 0 && x + width <= WIDTH);
+        assert!(height > 0 && y + height <= HEIGHT);
+
+        let end_x = x + width - 1;
+        let end_y = y + height - 1;
+
+        // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
+        // aren't relevant
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[(x >> 3) as u8, (end_x >> 3) as u8],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[y as u8, (y >> 8) as u8, end_y as u8, (end_y >> 8) as u8],
+        )
+    }
+
+    fn set_ram_counter(&mut self, spi: &mut SPI, x: u32, y: u32) -> Result<(), SPI::Error> {
+        self.cmd_with_data(spi, Command::SetRamXAddressCounter, &[(x >> 3) as u8])?;
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 128);
+        assert_eq!(HEIGHT, 296);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+}
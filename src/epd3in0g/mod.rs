@@ -0,0 +1,85 @@
+//! A simple Driver for the Waveshare 3.0" (G) E-Ink Display via SPI
+//!
+//! The panel shows black, white, yellow and red, drawn with [`QuadColor`] on a
+//! `Display3in0g`, whose buffer is sent to the controller unchanged. The driver is
+//! [`EpdG`] with the [`Panel3in0g`] settings.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/3inch_e-Paper_Module_(G))
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_3in0g.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd3in0g.py)
+
+use crate::color::QuadColor;
+use crate::interface::ResetProfile;
+use crate::type_g::{command::Command, EpdG, GPanel, Panel, Sequence};
+
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 3in0g EPD
+#[cfg(feature = "graphics")]
+pub type Display3in0g = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize * 2, HEIGHT as usize) },
+    QuadColor,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 168;
+/// Height of the display
+pub const HEIGHT: u32 = 400;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: QuadColor = QuadColor::White;
+const RESET: ResetProfile = ResetProfile::new(20_000, 2_000);
+
+/// Initialisation from the Waveshare sample code
+const INIT_SEQUENCE: &Sequence = &[
+    (Command::AnalogSetting, &[0x78]),
+    (Command::PanelSetting, &[0x0F, 0x29]),
+    (Command::PowerSetting, &[0x07, 0x00]),
+    (Command::PowerOffSequenceSetting, &[0x10, 0x54, 0x44]),
+    (
+        Command::BoosterSoftStart2,
+        &[0x05, 0x00, 0x3F, 0x0A, 0x25, 0x12, 0x1A],
+    ),
+    (Command::VcomAndDataIntervalSetting, &[0x37]),
+    (Command::TconSetting, &[0x02, 0x02]),
+    (Command::TconResolution, &[0x00, 0xA8, 0x01, 0x90]),
+    (Command::ManufacturerE7, &[0x1C]),
+    (Command::PowerSaving, &[0x22]),
+    (Command::ManufacturerB4, &[0xD0]),
+    (Command::ManufacturerB5, &[0x03]),
+    (Command::ManufacturerE9, &[0x01]),
+    (Command::PllControl, &[0x08]),
+];
+
+/// The 3in0g panel for [`EpdG`]
+#[derive(Clone, Copy, Debug)]
+pub struct Panel3in0g;
+
+impl GPanel for Panel3in0g {
+    const PANEL: Panel = Panel {
+        width: WIDTH,
+        height: HEIGHT,
+        reset: RESET,
+        init: INIT_SEQUENCE,
+    };
+}
+
+/// Epd3in0g driver
+pub type Epd3in0g<SPI, BUSY, DC, RST, DELAY> = EpdG<Panel3in0g, SPI, BUSY, DC, RST, DELAY>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 168);
+        assert_eq!(HEIGHT, 400);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, QuadColor::White);
+    }
+}
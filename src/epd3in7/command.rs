@@ -0,0 +1,87 @@
+//! SPI Commands for the Waveshare 3.7" E-Ink Display
+
+use crate::traits;
+
+/// Epd3in7 commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// For more infos about the addresses and what they are doing look into the PDFs.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Set gate number, scanning sequence and direction
+    GateSetting = 0x01,
+    /// Set gate driving voltage
+    GateVoltage = 0x03,
+    /// Set source driving voltage
+    GateVoltageSource = 0x04,
+    /// Booster soft start control
+    BoosterSoftStartControl = 0x0C,
+    /// Deep sleep mode
+    ///
+    /// 0x03: mode 2, RAM is not retained
+    DeepSleepMode = 0x10,
+    /// Define data entry sequence, X/Y increment or decrement
+    DataEntrySequence = 0x11,
+    /// Resets the commands and parameters to their S/W Reset default values except R10h-Deep Sleep Mode.
+    /// During operation, BUSY pad will output high.
+    SwReset = 0x12,
+    /// Temperature sensor selection, 0x80 for the internal sensor
+    TemperatureSensorSelection = 0x18,
+    /// Write to temperature register
+    TemperatureSensorWrite = 0x1A,
+    /// Activate display update sequence
+    ///
+    /// The Display Update Sequence Option is located at R22h.
+    /// BUSY pad will output high during operation. User should not interrupt this operation
+    /// to avoid corruption of panel images.
+    DisplayUpdateSequence = 0x20,
+    /// Display update sequence option
+    DisplayUpdateSequenceSetting = 0x22,
+    /// After this command, data entries will be written into the BW RAM until another command is written
+    WriteRam = 0x24,
+    /// After this command, data entries will be written into the second (old data) RAM
+    /// until another command is written
+    WriteRam2 = 0x26,
+    /// Write VCOM register
+    WriteVcomRegister = 0x2C,
+    /// Write the LUT register (105 bytes)
+    WriteLutRegister = 0x32,
+    /// Display option, enables the ping-pong mode needed for fast partial updates
+    DisplayOption = 0x37,
+    /// Select border waveform for VBD
+    BorderWaveformControl = 0x3C,
+    /// Specify the start/end positions of the window address in the X direction
+    SetRamXAddressStartEndPosition = 0x44,
+    /// Specify the start/end positions of the window address in the Y direction
+    SetRamYAddressStartEndPosition = 0x45,
+    /// Auto write the second RAM with a regular pattern
+    AutoWriteRedRamRegularPattern = 0x46,
+    /// Auto write the BW RAM with a regular pattern
+    AutoWriteBwRamRegularPattern = 0x47,
+    /// Make initial settings for the RAM X address in the address counter (AC)
+    SetRamXAddressCounter = 0x4E,
+    /// Make initial settings for the RAM Y address in the address counter (AC)
+    SetRamYAddressCounter = 0x4F,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::GateSetting.address(), 0x01);
+        assert_eq!(Command::WriteLutRegister.address(), 0x32);
+        assert_eq!(Command::SetRamYAddressCounter.address(), 0x4F);
+    }
+}
@@ -0,0 +1,438 @@
+//! A simple Driver for the Waveshare 3.7" E-Ink Display via SPI
+//!
+//! The panel is 280 pixels wide and 480 pixels high, rotate the display for a 480×280
+//! landscape layout.
+//!
+//! # Refresh modes
+//!
+//! - [`RefreshLut::Full`] uses the black/white global clear waveform
+//! - [`RefreshLut::Quick`] uses the direct update waveform, which is fast and without
+//!   flashing and meant for partial updates with [`WaveshareDisplay::update_partial_frame`]
+//! - [`Epd3in7::update_and_display_gray4_frame`] shows four levels of grey, e.g. drawn with
+//!   [`Gray4Color`] on a `Display3in7Gray4` for anti-aliased text
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/3.7inch_e-Paper_HAT)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_3in7.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd3in7.py)
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::buffer_len;
+use crate::color::{Color, Gray4Color};
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+use crate::traits::{InternalWiAdditions, RefreshLut, WaveshareDisplay};
+
+pub(crate) mod command;
+mod constants;
+
+use self::command::Command;
+use self::constants::*;
+
+/// Full size buffer for use with the 3in7 EPD
+#[cfg(feature = "graphics")]
+pub type Display3in7 = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize, HEIGHT as usize) },
+    Color,
+>;
+
+/// Full size buffer for the 4-grey mode, see [`Epd3in7::update_and_display_gray4_frame`]
+#[cfg(feature = "graphics")]
+pub type Display3in7Gray4 =
+    crate::graphics::Display<WIDTH, HEIGHT, false, GRAY4_BUFFER_LEN, Gray4Color>;
+
+/// Width of the display
+pub const WIDTH: u32 = 280;
+/// Height of the display
+pub const HEIGHT: u32 = 480;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+/// Number of bytes of a full frame in 4-grey mode (2 bits per pixel)
+pub const GRAY4_BUFFER_LEN: usize = buffer_len(WIDTH as usize * 2, HEIGHT as usize);
+const IS_BUSY_LOW: bool = false;
+const RESET: ResetProfile = ResetProfile::new(30_000, 10_000);
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// Bytes of one line in black/white mode
+const LINE_BYTES: usize = WIDTH as usize / 8;
+
+/// Epd3in7 driver
+///
+pub struct Epd3in7<SPI, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Background Color
+    color: Color,
+    /// Refresh LUT
+    refresh: RefreshLut,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // enables the ping-pong RAM option used by the direct update waveform
+        self.init_with_display_option(
+            spi,
+            delay,
+            &[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x4F, 0xFF, 0xFF, 0xFF, 0xFF],
+        )?;
+        self.set_lut(spi, delay, Some(self.refresh))
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, BUSY, DC, RST, DELAY>
+    for Epd3in7<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = Color;
+
+    fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let interface = DisplayInterface::new(busy, dc, rst, delay_us);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Epd3in7 {
+            interface,
+            color,
+            refresh: RefreshLut::Full,
+        };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleepMode, &[0x03])?;
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi)?;
+        self.cmd_with_data(spi, Command::WriteRam, buffer)
+    }
+
+    /// Updates a part of the frame
+    ///
+    /// `x` and `width` are rounded to multiples of 8 pixels, as the RAM is written
+    /// in whole bytes. Combine with [`RefreshLut::Quick`] for a fast partial refresh.
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        let (start_x, end_x, start_y, end_y) = match ram_window(x, y, width, height) {
+            Some(window) => window,
+            None => return Ok(()),
+        };
+
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, start_x, start_y, end_x, end_y)?;
+        self.set_ram_counter(spi, start_x, start_y)?;
+        self.cmd_with_data(spi, Command::WriteRam, buffer)
+    }
+
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.command(spi, Command::DisplayUpdateSequence)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let bg = self.color.get_byte_value();
+
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi)?;
+        self.command(spi, Command::WriteRam)?;
+        self.interface.data_x_times(spi, bg, WIDTH * HEIGHT / 8)?;
+        self.command(spi, Command::WriteRam2)?;
+        self.interface.data_x_times(spi, bg, WIDTH * HEIGHT / 8)?;
+
+        self.display_frame(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), SPI::Error> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        let lut = match self.refresh {
+            RefreshLut::Full | RefreshLut::Gray4 => &LUT_1GRAY_GC,
+            RefreshLut::Quick => &LUT_1GRAY_DU,
+        };
+
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::WriteLutRegister, lut)
+    }
+
+    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST> for Epd3in7<SPI, BUSY, DC, RST, DELAY> {
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> Epd3in7<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Transmits and displays a full frame in 4-grey mode
+    ///
+    /// The buffer holds 2 bits per pixel, 4 pixels per byte with the leftmost pixel in the
+    /// highest bits, as drawn with [`Gray4Color`] on a `Display3in7Gray4`.
+    /// It needs to be [`GRAY4_BUFFER_LEN`] bytes long.
+    ///
+    /// Call [`WaveshareDisplay::wake_up`] afterwards to go back to the black/white modes.
+    pub fn update_and_display_gray4_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.init_with_display_option(spi, delay, &[0x00; 10])?;
+        self.use_full_frame(spi)?;
+
+        // the low bit of every pixel goes to the BW RAM, the high bit to the second RAM
+        for (command, shift) in [(Command::WriteRam, 0), (Command::WriteRam2, 1)] {
+            self.set_ram_counter(spi, 0, 0)?;
+            self.command(spi, command)?;
+            let mut line = [0u8; LINE_BYTES];
+            for pixels in buffer.chunks(LINE_BYTES * 2) {
+                for (byte, pixels) in line.iter_mut().zip(pixels.chunks(2)) {
+                    *byte = Gray4Color::plane_byte(pixels, shift);
+                }
+                self.send_data(spi, &line[..(pixels.len() + 1) / 2])?;
+            }
+        }
+
+        self.cmd_with_data(spi, Command::WriteLutRegister, &LUT_4GRAY_GC)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn init_with_display_option(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        display_option: &[u8],
+    ) -> Result<(), SPI::Error> {
+        // reset the device
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
+
+        self.command(spi, Command::SwReset)?;
+        delay.delay_us(300_000);
+
+        self.cmd_with_data(spi, Command::AutoWriteRedRamRegularPattern, &[0xF7])?;
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::AutoWriteBwRamRegularPattern, &[0xF7])?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.cmd_with_data(spi, Command::GateSetting, &[0xDF, 0x01, 0x00])?;
+        self.cmd_with_data(spi, Command::GateVoltage, &[0x00])?;
+        self.cmd_with_data(spi, Command::GateVoltageSource, &[0x41, 0xA8, 0x32])?;
+        // X increment, Y increment
+        self.cmd_with_data(spi, Command::DataEntrySequence, &[0x03])?;
+        self.cmd_with_data(spi, Command::BorderWaveformControl, &[0x03])?;
+        self.cmd_with_data(
+            spi,
+            Command::BoosterSoftStartControl,
+            &[0xAE, 0xC7, 0xC3, 0xC0, 0xC0],
+        )?;
+        self.cmd_with_data(spi, Command::TemperatureSensorSelection, &[0x80])?;
+        self.cmd_with_data(spi, Command::WriteVcomRegister, &[0x44])?;
+        self.cmd_with_data(spi, Command::DisplayOption, display_option)?;
+        self.use_full_frame(spi)?;
+        self.cmd_with_data(spi, Command::DisplayUpdateSequenceSetting, &[0xCF])?;
+        Ok(())
+    }
+
+    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
+        self.interface.cmd(spi, command)
+    }
+
+    fn send_data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+        self.interface.data(spi, data)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.interface.cmd_with_data(spi, command, data)
+    }
+
+    fn use_full_frame(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        // choose full frame/ram
+        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+
+        // start from the beginning
+        self.set_ram_counter(spi, 0, 0)
+    }
+
+    fn set_ram_area(
+        &mut self,
+        spi: &mut SPI,
+        start_x: u32,
+        start_y: u32,
+        end_x: u32,
+        end_y: u32,
+    ) -> Result<(), SPI::Error> {
+        assert!(start_x <= end_x);
+        assert!(start_y <= end_y);
+
+        // the X address is counted in pixels on this controller
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[
+                start_x as u8,
+                (start_x >> 8) as u8,
+                end_x as u8,
+                (end_x >> 8) as u8,
+            ],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[
+                start_y as u8,
+                (start_y >> 8) as u8,
+                end_y as u8,
+                (end_y >> 8) as u8,
+            ],
+        )
+    }
+
+    fn set_ram_counter(&mut self, spi: &mut SPI, x: u32, y: u32) -> Result<(), SPI::Error> {
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressCounter,
+            &[x as u8, (x >> 8) as u8],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8],
+        )
+    }
+}
+
+/// RAM window of an area of the display
+///
+/// Returns the X start and end aligned to 8-pixel columns and the Y start and end, or
+/// `None` for an empty area.
+fn ram_window(x: u32, y: u32, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let start_x = x & !0x07;
+    let end_x = (x + width - 1) | 0x07;
+    Some((start_x, end_x, y, y + height - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 280);
+        assert_eq!(HEIGHT, 480);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+        assert_eq!(GRAY4_BUFFER_LEN, 33_600);
+    }
+
+    #[test]
+    fn ram_window_alignment() {
+        assert_eq!(ram_window(0, 0, WIDTH, HEIGHT), Some((0, 279, 0, 479)));
+        assert_eq!(ram_window(5, 3, 8, 1), Some((0, 15, 3, 3)));
+        assert_eq!(ram_window(8, 0, 8, 2), Some((8, 15, 0, 1)));
+        assert_eq!(ram_window(8, 0, 0, 2), None);
+        assert_eq!(ram_window(8, 0, 8, 0), None);
+    }
+}
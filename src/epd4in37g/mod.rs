@@ -0,0 +1,78 @@
+//! A simple Driver for the Waveshare 4.37" (G) E-Ink Display via SPI
+//!
+//! The panel shows black, white, yellow and red, drawn with [`QuadColor`] on a
+//! `Display4in37g`, whose buffer is sent to the controller unchanged. The driver is
+//! [`EpdG`] with the [`Panel4in37g`] settings.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/4.37inch_e-Paper_Module_(G))
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_4in37g.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd4in37g.py)
+
+use crate::color::QuadColor;
+use crate::interface::ResetProfile;
+use crate::type_g::{command::Command, EpdG, GPanel, Panel, Sequence};
+
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 4in37g EPD
+#[cfg(feature = "graphics")]
+pub type Display4in37g = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize * 2, HEIGHT as usize) },
+    QuadColor,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 512;
+/// Height of the display
+pub const HEIGHT: u32 = 368;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: QuadColor = QuadColor::White;
+const RESET: ResetProfile = ResetProfile::new(20_000, 2_000);
+
+/// Initialisation from the Waveshare sample code
+const INIT_SEQUENCE: &Sequence = &[
+    (Command::AnalogSetting, &[0x78]),
+    (Command::PanelSetting, &[0x0F, 0x29]),
+    (
+        Command::BoosterSoftStart2,
+        &[0x0D, 0x12, 0x30, 0x20, 0x19, 0x2A, 0x22],
+    ),
+    (Command::VcomAndDataIntervalSetting, &[0x37]),
+    (Command::TconResolution, &[0x02, 0x00, 0x01, 0x70]),
+    (Command::ManufacturerE9, &[0x01]),
+    (Command::PllControl, &[0x08]),
+];
+
+/// The 4in37g panel for [`EpdG`]
+#[derive(Clone, Copy, Debug)]
+pub struct Panel4in37g;
+
+impl GPanel for Panel4in37g {
+    const PANEL: Panel = Panel {
+        width: WIDTH,
+        height: HEIGHT,
+        reset: RESET,
+        init: INIT_SEQUENCE,
+    };
+}
+
+/// Epd4in37g driver
+pub type Epd4in37g<SPI, BUSY, DC, RST, DELAY> = EpdG<Panel4in37g, SPI, BUSY, DC, RST, DELAY>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 512);
+        assert_eq!(HEIGHT, 368);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, QuadColor::White);
+    }
+}
@@ -0,0 +1,110 @@
+//! SPI Commands for the Waveshare 7.3"F E-Ink Display
+
+use crate::traits;
+
+/// Epd7in3f commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// The names follow the Waveshare C driver, most of these commands are not documented
+/// in a public datasheet.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Set Resolution, LUT selection, BWR pixels, gate scan direction, source shift
+    /// direction, booster switch, soft reset.
+    PanelSetting = 0x00,
+
+    /// Selecting internal and external power
+    PowerSetting = 0x01,
+
+    /// After the Power Off command, the driver will power off following the Power Off
+    /// Sequence; BUSY signal will become "0".
+    PowerOff = 0x02,
+
+    /// Setting Power OFF sequence
+    PowerOffSequenceSetting = 0x03,
+
+    /// Turning On the Power
+    PowerOn = 0x04,
+
+    /// Booster soft start setting of phase 1
+    BoosterSoftStart1 = 0x05,
+
+    /// Booster soft start setting of phase 2
+    BoosterSoftStart2 = 0x06,
+
+    /// This command makes the chip enter the deep-sleep mode to save power.
+    ///
+    /// The only one parameter is a check code, the command would be excuted if check code = 0xA5.
+    DeepSleep = 0x07,
+
+    /// Booster soft start setting of phase 3
+    BoosterSoftStart3 = 0x08,
+
+    /// This command starts transmitting data and write them into SRAM.
+    DataStartTransmission = 0x10,
+
+    /// After this command is issued, driver will refresh display (data/VCOM) according to
+    /// SRAM data and LUT.
+    DisplayRefresh = 0x12,
+
+    /// Image Process Command
+    ImageProcess = 0x13,
+
+    /// The command controls the PLL clock frequency.
+    PllControl = 0x30,
+
+    /// This command selects the Internal or External temperature sensor.
+    TemperatureSensorEnable = 0x41,
+
+    /// This command indicates the interval of Vcom and data output.
+    VcomAndDataIntervalSetting = 0x50,
+
+    /// This command defines non-overlap period of Gate and Source.
+    TconSetting = 0x60,
+
+    /// This command defines alternative resolution.
+    TconResolution = 0x61,
+
+    /// This command sets `VCOM_DC` value.
+    VcmDcSetting = 0x82,
+
+    /// VCOM DC setting during power off (T_VDCS)
+    VcomDcPowerOff = 0x84,
+
+    /// Auto gate idle (AGID)
+    AutoGateIdle = 0x86,
+
+    /// Command header (CMDH), unlocks the manufacturer commands
+    Cmdh = 0xAA,
+
+    /// Cascade Setting (CCSET)
+    CascadeSetting = 0xE0,
+
+    /// Power Saving (PWS)
+    PowerSaving = 0xE3,
+
+    /// Force temperature (TSSET)
+    ForceTemperature = 0xE6,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::PanelSetting.address(), 0x00);
+        assert_eq!(Command::DisplayRefresh.address(), 0x12);
+        assert_eq!(Command::Cmdh.address(), 0xAA);
+    }
+}
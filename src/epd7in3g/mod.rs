@@ -0,0 +1,81 @@
+//! A simple Driver for the Waveshare 7.3" (G) E-Ink Display via SPI
+//!
+//! The panel shows black, white, yellow and red, drawn with [`QuadColor`] on a
+//! `Display7in3g`, whose buffer is sent to the controller unchanged. The driver is
+//! [`EpdG`] with the [`Panel7in3g`] settings.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/7.3inch_e-Paper_HAT_(G))
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_7in3g.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd7in3g.py)
+
+use crate::color::QuadColor;
+use crate::interface::ResetProfile;
+use crate::type_g::{command::Command, EpdG, GPanel, Panel, Sequence};
+
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 7in3g EPD
+#[cfg(feature = "graphics")]
+pub type Display7in3g = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize * 2, HEIGHT as usize) },
+    QuadColor,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 800;
+/// Height of the display
+pub const HEIGHT: u32 = 480;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: QuadColor = QuadColor::White;
+const RESET: ResetProfile = ResetProfile::new(20_000, 2_000);
+
+/// Initialisation from the Waveshare sample code
+const INIT_SEQUENCE: &Sequence = &[
+    (Command::Cmdh, &[0x49, 0x55, 0x20, 0x08, 0x09, 0x18]),
+    (Command::PowerSetting, &[0x3F]),
+    (Command::PanelSetting, &[0x4F, 0x69]),
+    (Command::BoosterSoftStart1, &[0x40, 0x1F, 0x1F, 0x2C]),
+    (Command::BoosterSoftStart3, &[0x6F, 0x1F, 0x1F, 0x22]),
+    (Command::BoosterSoftStart2, &[0x6F, 0x1F, 0x14, 0x14]),
+    (Command::PowerOffSequenceSetting, &[0x00, 0x54, 0x00, 0x44]),
+    (Command::TconSetting, &[0x02, 0x00]),
+    (Command::PllControl, &[0x08]),
+    (Command::VcomAndDataIntervalSetting, &[0x3F]),
+    (Command::TconResolution, &[0x03, 0x20, 0x01, 0xE0]),
+    (Command::PowerSaving, &[0x2F]),
+    (Command::VcomDcPowerOff, &[0x01]),
+];
+
+/// The 7in3g panel for [`EpdG`]
+#[derive(Clone, Copy, Debug)]
+pub struct Panel7in3g;
+
+impl GPanel for Panel7in3g {
+    const PANEL: Panel = Panel {
+        width: WIDTH,
+        height: HEIGHT,
+        reset: RESET,
+        init: INIT_SEQUENCE,
+    };
+}
+
+/// Epd7in3g driver
+pub type Epd7in3g<SPI, BUSY, DC, RST, DELAY> = EpdG<Panel7in3g, SPI, BUSY, DC, RST, DELAY>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 800);
+        assert_eq!(HEIGHT, 480);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, QuadColor::White);
+    }
+}
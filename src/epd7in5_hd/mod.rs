@@ -0,0 +1,356 @@
+//! A simple Driver for the Waveshare 7.5" HD E-Ink Display (880×528) via SPI
+//!
+//! The panel uses a SSD1677 controller, which addresses its RAM in pixels in X direction.
+//! As the data is still written in whole bytes, partial windows are widened to
+//! 8-pixel columns.
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/7.5inch_HD_e-Paper_HAT)
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_7in5_HD.c)
+//! - [Waveshare Python driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd7in5_HD.py)
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::color::Color;
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+use crate::traits::{InternalWiAdditions, RefreshLut, WaveshareDisplay};
+
+pub(crate) mod command;
+use self::command::Command;
+#[cfg(feature = "graphics")]
+use crate::buffer_len;
+
+/// Full size buffer for use with the 7in5 HD EPD
+#[cfg(feature = "graphics")]
+pub type Display7in5 = crate::graphics::Display<
+    WIDTH,
+    HEIGHT,
+    false,
+    { buffer_len(WIDTH as usize, HEIGHT as usize) },
+    Color,
+>;
+
+/// Width of the display
+pub const WIDTH: u32 = 880;
+/// Height of the display
+pub const HEIGHT: u32 = 528;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+const IS_BUSY_LOW: bool = false;
+/// Reset pulse, the controller signals the end of its reset on BUSY
+const RESET: ResetProfile = ResetProfile::new(10_000, 2_000).with_busy_wait(10_000);
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// RAM row of the first display line
+///
+/// The gates are scanned in reverse and the Y address counts down from here.
+const RAM_Y_TOP: u32 = 0x2AF;
+
+/// Epd7in5 (HD) driver
+///
+pub struct Epd7in5<SPI, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Background Color
+    color: Color,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, BUSY, DC, RST, DELAY>
+    for Epd7in5<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Reset the device
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
+
+        self.command(spi, Command::SwReset)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.cmd_with_data(spi, Command::AutoWriteRedRamRegularPattern, &[0xF7])?;
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::AutoWriteBwRamRegularPattern, &[0xF7])?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.cmd_with_data(
+            spi,
+            Command::BoosterSoftStartControl,
+            &[0xAE, 0xC7, 0xC3, 0xC0, 0x40],
+        )?;
+        // 688 gates, scanned from the last one to the first one
+        self.cmd_with_data(
+            spi,
+            Command::DriverOutputControl,
+            &[RAM_Y_TOP as u8, (RAM_Y_TOP >> 8) as u8, 0x01],
+        )?;
+        // X increment, Y decrement
+        self.cmd_with_data(spi, Command::DataEntryModeSetting, &[0x01])?;
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
+        self.cmd_with_data(spi, Command::BorderWaveformControl, &[0x05])?;
+        self.cmd_with_data(spi, Command::TemperatureSensorControl, &[0x80])?;
+
+        // load temperature and waveform
+        self.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xB1])?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.set_ram_counter(spi, 0, 0)
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, BUSY, DC, RST, DELAY>
+    for Epd7in5<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = Color;
+
+    fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let interface = DisplayInterface::new(busy, dc, rst, delay_us);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Epd7in5 { interface, color };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
+        self.set_ram_counter(spi, 0, 0)?;
+        self.cmd_with_data(spi, Command::WriteRamBw, buffer)
+    }
+
+    /// Updates a part of the frame
+    ///
+    /// `x` is rounded down and `x + width` rounded up to the next multiple of 8 pixels.
+    /// `buffer` holds the lines of that widened window, each of them
+    /// `((x + width + 7) / 8 - x / 8)` bytes long.
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, x, y, width, height)?;
+        self.set_ram_counter(spi, x, y)?;
+        self.cmd_with_data(spi, Command::WriteRamBw, buffer)
+    }
+
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xF7])?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let bg = self.color.get_byte_value();
+
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
+        for command in [Command::WriteRamBw, Command::WriteRamRed] {
+            self.set_ram_counter(spi, 0, 0)?;
+            self.command(spi, command)?;
+            self.interface.data_x_times(spi, bg, WIDTH * HEIGHT / 8)?;
+        }
+
+        self.display_frame(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    /// The display has a single waveform, there is no LUT to switch to
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), SPI::Error> {
+        Ok(())
+    }
+
+    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST> for Epd7in5<SPI, BUSY, DC, RST, DELAY> {
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> Epd7in5<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
+        self.interface.cmd(spi, command)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.interface.cmd_with_data(spi, command, data)
+    }
+
+    fn set_ram_area(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        let (start_x, end_x, start_y, end_y) = ram_window(x, y, width, height);
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[
+                start_x as u8,
+                (start_x >> 8) as u8,
+                end_x as u8,
+                (end_x >> 8) as u8,
+            ],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[
+                start_y as u8,
+                (start_y >> 8) as u8,
+                end_y as u8,
+                (end_y >> 8) as u8,
+            ],
+        )
+    }
+
+    fn set_ram_counter(&mut self, spi: &mut SPI, x: u32, y: u32) -> Result<(), SPI::Error> {
+        let x = x & !0x07;
+        let y = RAM_Y_TOP - y;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressCounter,
+            &[x as u8, (x >> 8) as u8],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8],
+        )
+    }
+}
+
+/// RAM window of an area of the display
+///
+/// Returns the X start and end in pixels, aligned to 8-pixel columns, and the Y start and
+/// end in RAM rows, which count down from [`RAM_Y_TOP`].
+fn ram_window(x: u32, y: u32, width: u32, height: u32) -> (u32, u32, u32, u32) {
+    assert!(width > 0 && x + width <= WIDTH);
+    assert!(height > 0 && y + height <= HEIGHT);
+
+    let start_x = x & !0x07;
+    let end_x = (x + width - 1) | 0x07;
+    let start_y = RAM_Y_TOP - y;
+    let end_y = RAM_Y_TOP - (y + height - 1);
+    (start_x, end_x, start_y, end_y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd_size() {
+        assert_eq!(WIDTH, 880);
+        assert_eq!(HEIGHT, 528);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+    }
+
+    #[test]
+    fn ram_window_alignment() {
+        assert_eq!(ram_window(0, 0, WIDTH, HEIGHT), (0, 879, 0x2AF, 0xA0));
+        // widened to whole bytes on both sides
+        assert_eq!(ram_window(3, 10, 10, 2), (0, 15, 0x2A5, 0x2A4));
+        assert_eq!(ram_window(8, 0, 8, 1), (8, 15, 0x2AF, 0x2AF));
+    }
+}
@@ -129,7 +129,7 @@ pub(crate) enum Command {
     /// The LUT_REV / Chip Revision is read from OTP address = 25001 and 25000.
     Revision = 0x70,
     /// This command reads the IC status.
-    // GetStatus = 0x71,
+    GetStatus = 0x71,
 
     /// This command implements related VCOM sensing setting.
     AutoMeasurementVcom = 0x80,
@@ -10,18 +10,16 @@ use embedded_hal::{
     spi::SpiDevice,
 };
 
-use crate::color::QuadColor;
 #[cfg(feature = "simulator")]
 use crate::color::ColorType;
-use crate::interface::DisplayInterface;
+use crate::color::QuadColor;
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
 use crate::traits::{InternalWiAdditions, RefreshLut, WaveshareDisplay};
 
 pub(crate) mod command;
 use self::command::Command;
 use crate::buffer_len;
 
-use log::info;
-
 #[cfg(feature = "simulator")]
 use embedded_graphics_core::prelude::*;
 
@@ -48,8 +46,18 @@ pub const DEFAULT_BACKGROUND_COLOR: QuadColor = QuadColor::White;
 /// Number of bytes for b/w buffer and same for chromatic buffer bits
 const NUM_DISPLAY_BITS: usize = WIDTH as usize / 4 * HEIGHT as usize;
 const IS_BUSY_LOW: bool = true;
+const RESET: ResetProfile = ResetProfile::new(20_000, 20_000);
 const SINGLE_BYTE_WRITE: bool = false;
 
+/// Revision read with [`Epd7in5::read_revision`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Revision {
+    /// LUT revision from the OTP
+    pub lut: u16,
+    /// Chip revision
+    pub chip: u8,
+}
+
 /// Epd7in5 (yrd0750ryf665f60) driver
 ///
 pub struct Epd7in5<SPI, BUSY, DC, RST, DELAY> {
@@ -75,7 +83,7 @@ where
     fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
         // Reset the device
         // 手册要求，RST先拉低10ms，再拉高10ms，然后等待屏幕空闲
-        self.interface.reset(delay, 20_000, 20_000);
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
         self.wait_until_idle(spi, delay)?;
 
         // 示例代码中的神秘命令，需要先执行这条命令才能正确初始化
@@ -148,6 +156,7 @@ where
         self.cmd_with_data(spi, Command::PowerOff, &[0x00])?;
         self.wait_until_idle(spi, delay)?;
         self.cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
+        self.interface.power_off(delay);
         Ok(())
     }
 
@@ -305,6 +314,12 @@ where
     }
 }
 
+impl<SPI, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST> for Epd7in5<SPI, BUSY, DC, RST, DELAY> {
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
 impl<SPI, BUSY, DC, RST, DELAY> Epd7in5<SPI, BUSY, DC, RST, DELAY>
 where
     SPI: SpiDevice,
@@ -357,6 +372,65 @@ where
         Ok(())
     }
 
+    /// Reads the temperature in °C from the temperature sensor
+    ///
+    /// Like all reads this needs MISO or a half duplex SDA line, see the crate docs.
+    pub fn read_temperature(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<i8, SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        // the second byte only holds the half degree
+        let mut data = [0; 2];
+        self.interface
+            .cmd_read(spi, Command::TemperatureSensor, 0, &mut data)?;
+        Ok(data[0] as i8)
+    }
+
+    /// Reads the LUT and chip revision
+    pub fn read_revision(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Revision, SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        let mut data = [0; 3];
+        self.interface
+            .cmd_read(spi, Command::Revision, 0, &mut data)?;
+        Ok(Revision {
+            lut: u16::from_be_bytes([data[0], data[1]]),
+            chip: data[2],
+        })
+    }
+
+    /// Reads the status flags of the controller
+    pub fn read_status(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<u8, SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        let mut data = [0];
+        self.interface
+            .cmd_read(spi, Command::GetStatus, 0, &mut data)?;
+        Ok(data[0])
+    }
+
+    /// Reads the VCOM value, in the steps of the `VcmDcSetting` command
+    pub fn read_vcom(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<u8, SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        let mut data = [0];
+        self.interface
+            .cmd_read(spi, Command::ReadVcomValue, 0, &mut data)?;
+        Ok(data[0])
+    }
+
+    /// Reads `buffer.len()` bytes of the MTP
+    pub fn read_mtp(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &mut [u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        // The first byte is a dummy byte
+        self.interface
+            .cmd_read(spi, Command::ReadMTPData, 1, buffer)
+    }
+
     fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
         self.interface.cmd(spi, command)
     }
@@ -396,4 +470,106 @@ mod tests {
         assert_eq!(HEIGHT, 480);
         assert_eq!(DEFAULT_BACKGROUND_COLOR, QuadColor::White);
     }
+
+    #[cfg(not(feature = "simulator"))]
+    mod reads {
+        use super::*;
+        use embedded_hal_mock::eh1::{
+            delay::NoopDelay,
+            digital::{Mock as PinMock, State, Transaction as PinTransaction},
+            spi::{Mock as SpiMock, Transaction as SpiTransaction},
+        };
+
+        extern crate std;
+        use std::vec;
+
+        type Epd = Epd7in5<SpiMock<u8>, PinMock, PinMock, PinMock, NoopDelay>;
+
+        /// A driver expecting one read transaction, without running the init sequence
+        fn expect_read(
+            command: u8,
+            dummy: usize,
+            answer: &[u8],
+        ) -> (Epd, SpiMock<u8>, [PinMock; 3]) {
+            let mut expectations = vec![
+                SpiTransaction::transaction_start(),
+                SpiTransaction::write_vec(vec![command]),
+            ];
+            if dummy > 0 {
+                expectations.push(SpiTransaction::read_vec(vec![0xFF; dummy]));
+            }
+            expectations.push(SpiTransaction::read_vec(answer.to_vec()));
+            expectations.push(SpiTransaction::transaction_end());
+
+            let busy = PinMock::new(&[PinTransaction::get(State::High)]);
+            let dc = PinMock::new(&[PinTransaction::set(State::Low)]);
+            let rst = PinMock::new(&[]);
+            let epd = Epd7in5 {
+                interface: DisplayInterface::new(busy.clone(), dc.clone(), rst.clone(), None),
+                color: DEFAULT_BACKGROUND_COLOR,
+            };
+            (epd, SpiMock::new(&expectations), [busy, dc, rst])
+        }
+
+        fn done(mut spi: SpiMock<u8>, pins: [PinMock; 3]) {
+            spi.done();
+            for mut pin in pins {
+                pin.done();
+            }
+        }
+
+        #[test]
+        fn temperature() {
+            let (mut epd, mut spi, pins) = expect_read(0x40, 0, &[0xF6, 0x80]);
+            let temperature = epd.read_temperature(&mut spi, &mut NoopDelay::new());
+            assert_eq!(temperature.unwrap(), -10);
+            done(spi, pins);
+
+            let (mut epd, mut spi, pins) = expect_read(0x40, 0, &[0x19, 0x00]);
+            let temperature = epd.read_temperature(&mut spi, &mut NoopDelay::new());
+            assert_eq!(temperature.unwrap(), 25);
+            done(spi, pins);
+        }
+
+        #[test]
+        fn revision() {
+            let (mut epd, mut spi, pins) = expect_read(0x70, 0, &[0x12, 0x34, 0x0A]);
+            let revision = epd.read_revision(&mut spi, &mut NoopDelay::new()).unwrap();
+            assert_eq!(
+                revision,
+                Revision {
+                    lut: 0x1234,
+                    chip: 0x0A
+                }
+            );
+            done(spi, pins);
+        }
+
+        #[test]
+        fn status_and_vcom() {
+            let (mut epd, mut spi, pins) = expect_read(0x71, 0, &[0x02]);
+            assert_eq!(
+                epd.read_status(&mut spi, &mut NoopDelay::new()).unwrap(),
+                0x02
+            );
+            done(spi, pins);
+
+            let (mut epd, mut spi, pins) = expect_read(0x81, 0, &[0x1E]);
+            assert_eq!(
+                epd.read_vcom(&mut spi, &mut NoopDelay::new()).unwrap(),
+                0x1E
+            );
+            done(spi, pins);
+        }
+
+        #[test]
+        fn mtp_skips_dummy_byte() {
+            let (mut epd, mut spi, pins) = expect_read(0x92, 1, &[1, 2, 3, 4]);
+            let mut mtp = [0; 4];
+            epd.read_mtp(&mut spi, &mut mtp, &mut NoopDelay::new())
+                .unwrap();
+            assert_eq!(mtp, [1, 2, 3, 4]);
+            done(spi, pins);
+        }
+    }
 }
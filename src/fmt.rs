@@ -0,0 +1,26 @@
+//! Logging macros routing the driver diagnostics to `defmt` or `log`, whichever is enabled
+//!
+//! `defmt` wins if both features are enabled. Without either one the arguments are only
+//! type checked.
+
+macro_rules! info {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "defmt")]
+        defmt::info!($($arg)*);
+        #[cfg(all(feature = "log", not(feature = "defmt")))]
+        log::info!($($arg)*);
+        #[cfg(not(any(feature = "log", feature = "defmt")))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "defmt")]
+        defmt::debug!($($arg)*);
+        #[cfg(all(feature = "log", not(feature = "defmt")))]
+        log::debug!($($arg)*);
+        #[cfg(not(any(feature = "log", feature = "defmt")))]
+        let _ = format_args!($($arg)*);
+    }};
+}
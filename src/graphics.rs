@@ -29,23 +29,25 @@ const fn line_bytes(width: u32, bits_per_pixel: usize) -> usize {
 ///
 /// - WIDTH: width in pixel when display is not rotated
 /// - HEIGHT: height in pixel when display is not rotated
-/// - BWRBIT: mandatory value of the B/W when chromatic bit is set, can be any value for non
-///           tricolor epd
+/// - BWRBIT: selects the black/white bit of chromatic pixels, can be any value for non
+///   tricolor epd
 /// - COLOR: color type used by the target display
 /// - BYTECOUNT: This is redundant with previous data and should be removed when const generic
-///              expressions are stabilized
+///   expressions are stabilized
 ///
 /// More on BWRBIT:
 ///
-/// Different chromatic displays differently treat the bits in chromatic color planes.
-/// Some of them ([crate::epd2in13bc]) will render a color pixel if bit is set for that pixel,
-/// which is a `BWRBIT = true` mode.
+/// Different chromatic displays differently treat the black/white bit of a chromatic pixel.
+/// Some of them ([crate::epd2in66b]) want it cleared, so the pixel is black in the black/white
+/// plane, which is a `BWRBIT = true` mode.
 ///
-/// Other displays, like [crate::epd5in83b_v2] in opposite, will draw color pixel if bit is
-/// cleared for that pixel, which is a `BWRBIT = false` mode.
+/// Other displays, like [crate::epd5in83b_v2] in opposite, draw the chromatic plane over a
+/// white pixel and want the white bit set, which is a `BWRBIT = false` mode.
 ///
-/// BWRBIT=true: chromatic doesn't override white, white bit cleared for black, white bit set for white, both bits set for chromatic
-/// BWRBIT=false: chromatic does override white, both bits cleared for black, white bit set for white, red bit set for black
+/// BWRBIT=true: white bit cleared and chromatic bit set for chromatic
+/// BWRBIT=false: white bit set and chromatic bit set for chromatic
+///
+/// In both modes black clears both bits and white only sets the white bit.
 pub struct Display<
     const WIDTH: u32,
     const HEIGHT: u32,
@@ -77,9 +79,8 @@ impl<
     #[inline(always)]
     fn default() -> Self {
         Self {
-            // default color must be 0 for every bit in a pixel to make this work everywhere
-            // 注意：这里强行硬编码了0x55作为白色，只能应用于这种4色墨水屏
-            buffer: [0x55; BYTECOUNT],
+            // default color must be the same for every pixel in a byte to make this work everywhere
+            buffer: [COLOR::DEFAULT_BYTE; BYTECOUNT],
             rotation: DisplayRotation::default(),
             _color: PhantomData,
         }
@@ -1,8 +1,13 @@
 #![allow(unused)]
 
+use crate::trace::{TraceEvent, TraceFn};
 use crate::traits::Command;
 use core::marker::PhantomData;
-use embedded_hal::{delay::*, digital::*, spi::SpiDevice};
+use embedded_hal::{
+    delay::*,
+    digital::*,
+    spi::{Operation, SpiDevice},
+};
 
 /// The Connection Interface of all (?) Waveshare EPD-Devices
 ///
@@ -21,8 +26,179 @@ pub(crate) struct DisplayInterface<SPI, BUSY, DC, RST, DELAY, const SINGLE_BYTE_
     rst: RST,
     /// number of ms the idle loop should sleep on
     delay_us: u32,
+    /// Settings changed through [`DisplaySettings`](crate::traits::DisplaySettings)
+    settings: Settings<BUSY, RST>,
 }
 
+/// Settings of a [`DisplayInterface`] that don't depend on its other pins
+///
+/// Not reachable from outside the crate, which seals
+/// [`DisplaySettings`](crate::traits::DisplaySettings).
+pub struct Settings<BUSY, RST> {
+    /// Maximum number of bytes per spi transfer
+    pub(crate) max_transfer_len: usize,
+    /// Called instead of the delay while the device is busy
+    pub(crate) busy_wait: Option<BusyWait<BUSY>>,
+    /// Called with everything sent to the device
+    pub(crate) trace: Option<TraceFn>,
+    /// Switches the supply of the device
+    pub(crate) power: Option<PowerPin<RST>>,
+    /// If the supply is switched off by [power_off()](DisplayInterface::power_off())
+    pub(crate) powered_off: bool,
+    /// Replaces the reset profile of the driver
+    pub(crate) reset: Option<ResetProfile>,
+}
+
+/// Gives access to the [`Settings`] of a driver's display interface
+///
+/// Drivers implement this instead of [`DisplaySettings`](crate::traits::DisplaySettings),
+/// which is implemented for all of them.
+pub trait HasSettings<BUSY, RST> {
+    /// The settings of the display interface
+    fn settings(&mut self) -> &mut Settings<BUSY, RST>;
+}
+
+/// Pin switching the supply of a display (high for on), e.g. the PWR pin of newer HATs
+///
+/// It has the same type as the RST pin, HALs with a type per pin offer a common type for
+/// both (e.g. a downgraded or type erased output pin). The display is powered on before
+/// every reset, which [`wake_up`](crate::prelude::WaveshareDisplay::wake_up) does, and
+/// powered off at the end of [`sleep`](crate::prelude::WaveshareDisplay::sleep).
+/// [`new_with_power`](crate::prelude::WaveshareDisplay::new_with_power) powers it on
+/// before initialising it.
+#[derive(Debug)]
+pub struct PowerPin<PIN> {
+    pin: PIN,
+    on_delay_us: u32,
+    off_delay_us: u32,
+}
+
+impl<PIN> PowerPin<PIN> {
+    /// Creates a power pin
+    ///
+    /// `on_delay_us` is waited after switching the supply on, `off_delay_us` after the
+    /// last command before switching it off.
+    pub fn new(pin: PIN, on_delay_us: u32, off_delay_us: u32) -> Self {
+        PowerPin {
+            pin,
+            on_delay_us,
+            off_delay_us,
+        }
+    }
+
+    /// Returns the pin
+    pub fn release(self) -> PIN {
+        self.pin
+    }
+
+    /// Switches the supply on and waits `on_delay_us`
+    pub(crate) fn switch_on<DELAY: DelayNs>(&mut self, delay: &mut DELAY)
+    where
+        PIN: OutputPin,
+    {
+        let _ = self.pin.set_high();
+        delay.delay_us(self.on_delay_us);
+    }
+}
+
+/// Timing of the reset pulse of a display
+///
+/// The timing of keeping the reset pin low seems to be important and different per device.
+/// Most displays seem to require keeping it low for 10ms, but the 7in5_v2 only seems to reset
+/// properly with 2ms. Every driver has its own profile, it can be replaced with
+/// [`DisplaySettings::set_reset_profile`](crate::traits::DisplaySettings::set_reset_profile).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ResetProfile {
+    /// Time RST is held high before the pulse, in us
+    pub initial_us: u32,
+    /// Length of the low pulse, in us
+    pub low_us: u32,
+    /// Time waited after the pulse, in us
+    pub wait_us: u32,
+    /// Waits for BUSY after `wait_us`, for controllers signalling the end of their reset
+    pub wait_for_busy: bool,
+}
+
+impl ResetProfile {
+    /// The given pulse followed by the 200ms the upstream libraries wait
+    pub const fn new(initial_us: u32, low_us: u32) -> Self {
+        ResetProfile {
+            initial_us,
+            low_us,
+            wait_us: 200_000,
+            wait_for_busy: false,
+        }
+    }
+
+    /// Waits `wait_us` after the pulse instead of the 200ms
+    pub const fn with_wait(self, wait_us: u32) -> Self {
+        ResetProfile { wait_us, ..self }
+    }
+
+    /// Waits `wait_us` and then for BUSY after the pulse, instead of the fixed 200ms
+    pub const fn with_busy_wait(self, wait_us: u32) -> Self {
+        ResetProfile {
+            wait_us,
+            wait_for_busy: true,
+            ..self
+        }
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY, const SINGLE_BYTE_WRITE: bool> HasSettings<BUSY, RST>
+    for DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>
+{
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        &mut self.settings
+    }
+}
+
+/// Size of the stack buffer used to send repeated bytes
+const REPEAT_BUFFER_LEN: usize = 256;
+/// Maximum number of dummy bytes dropped by [`DisplayInterface::cmd_read`]
+pub(crate) const MAX_DUMMY_BYTES: usize = 2;
+
+/// Waits for the BUSY pin, e.g. by sleeping until its edge interrupt fires
+///
+/// It gets the pin and the level it will have once the display is idle. It may return
+/// at any time (e.g. on any interrupt), the pin is checked again afterwards and the
+/// wait called once more if the display is still busy.
+pub enum BusyWait<BUSY> {
+    /// A plain function
+    Fn(fn(&mut BUSY, PinState)),
+    /// A closure keeping state such as a waker, a peripheral handle or a timeout
+    ///
+    /// It only gets the idle level, the pin stays with the driver. It has to live as long
+    /// as the driver, e.g. in a `static_cell::StaticCell`.
+    Closure(&'static mut dyn FnMut(PinState)),
+}
+
+impl<BUSY> BusyWait<BUSY> {
+    fn wait(&mut self, busy: &mut BUSY, idle: PinState) {
+        match self {
+            BusyWait::Fn(wait) => wait(busy, idle),
+            BusyWait::Closure(wait) => wait(idle),
+        }
+    }
+}
+
+impl<BUSY> From<fn(&mut BUSY, PinState)> for BusyWait<BUSY> {
+    fn from(wait: fn(&mut BUSY, PinState)) -> Self {
+        BusyWait::Fn(wait)
+    }
+}
+
+/// Default maximum number of bytes per spi transfer
+///
+/// Linux has a default limit of 4096 bytes per spi transfer (`bufsiz` of spidev),
+/// see <https://raspberrypi.stackexchange.com/questions/65595/spi-transfer-fails-with-buffer-size-greater-than-4096>.
+/// Elsewhere the data is sent as it is.
+pub const DEFAULT_MAX_TRANSFER_LEN: usize = if cfg!(target_os = "linux") {
+    4096
+} else {
+    usize::MAX
+};
+
 impl<SPI, BUSY, DC, RST, DELAY, const SINGLE_BYTE_WRITE: bool>
     DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>
 where
@@ -45,6 +221,45 @@ where
             dc,
             rst,
             delay_us,
+            settings: Settings {
+                max_transfer_len: DEFAULT_MAX_TRANSFER_LEN,
+                busy_wait: None,
+                trace: None,
+                power: None,
+                powered_off: false,
+                reset: None,
+            },
+        }
+    }
+
+    /// Switches the supply of the device on, if it was switched off
+    pub(crate) fn power_on(&mut self, delay: &mut DELAY) {
+        if !self.settings.powered_off {
+            return;
+        }
+        if let Some(power) = &mut self.settings.power {
+            power.switch_on(delay);
+        }
+        self.settings.powered_off = false;
+    }
+
+    /// Switches the supply of the device off, if there is a power pin
+    ///
+    /// DC and RST are pulled low as well, so the device isn't powered through them.
+    pub(crate) fn power_off(&mut self, delay: &mut DELAY) {
+        if let Some(power) = &mut self.settings.power {
+            delay.delay_us(power.off_delay_us);
+            let _ = power.pin.set_low();
+            let _ = self.dc.set_low();
+            let _ = self.rst.set_low();
+            self.settings.powered_off = true;
+        }
+    }
+
+    /// Passes `event` to the trace function, if there is one
+    pub(crate) fn trace(&self, event: TraceEvent) {
+        if let Some(trace) = self.settings.trace {
+            trace(event);
         }
     }
 
@@ -52,6 +267,8 @@ where
     ///
     /// Enables direct interaction with the device with the help of [data()](DisplayInterface::data())
     pub(crate) fn cmd<T: Command>(&mut self, spi: &mut SPI, command: T) -> Result<(), SPI::Error> {
+        self.trace(TraceEvent::Command(command.address()));
+
         // low for commands
         let _ = self.dc.set_low();
 
@@ -63,6 +280,8 @@ where
     ///
     /// Enables direct interaction with the device with the help of [command()](Epd4in2::command())
     pub(crate) fn data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+        self.trace(TraceEvent::data(data));
+
         // high for data
         let _ = self.dc.set_high();
 
@@ -80,7 +299,9 @@ where
 
     /// Basic function for sending [Commands](Command) and the data belonging to it.
     ///
-    /// TODO: directly use ::write? cs wouldn't needed to be changed twice than
+    /// Command and data can't share one transaction: a [`SpiDevice`] gives no way to
+    /// switch DC in between its operations, so CS is released after the command.
+    /// Commands without data are sent on their own.
     pub(crate) fn cmd_with_data<T: Command>(
         &mut self,
         spi: &mut SPI,
@@ -88,9 +309,52 @@ where
         data: &[u8],
     ) -> Result<(), SPI::Error> {
         self.cmd(spi, command)?;
+        if data.is_empty() {
+            return Ok(());
+        }
         self.data(spi, data)
     }
 
+    /// Basic function for sending a read [Command](Command) and reading its answer
+    ///
+    /// Command and read phase share one transaction, so CS stays low in between as the
+    /// half duplex SDA controllers require. DC stays low during the read phase, the
+    /// controllers drive the line and ignore it. The first `dummy` bytes of the answer
+    /// (at most [`MAX_DUMMY_BYTES`]) are dropped.
+    ///
+    /// The display only answers if MISO is connected or if the [`SpiDevice`] turns the
+    /// bidirectional SDA line around for its read phase (3-wire half duplex).
+    pub(crate) fn cmd_read<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+        dummy: usize,
+        buffer: &mut [u8],
+    ) -> Result<(), SPI::Error> {
+        self.trace(TraceEvent::Command(command.address()));
+        self.trace(TraceEvent::Read { len: buffer.len() });
+
+        // low for commands
+        let _ = self.dc.set_low();
+
+        if cfg!(feature = "simulator") {
+            // Nothing to read from in simulator
+            return Ok(());
+        }
+
+        let command = [command.address()];
+        let mut skipped = [0; MAX_DUMMY_BYTES];
+        if dummy == 0 {
+            spi.transaction(&mut [Operation::Write(&command), Operation::Read(buffer)])
+        } else {
+            spi.transaction(&mut [
+                Operation::Write(&command),
+                Operation::Read(&mut skipped[..dummy]),
+                Operation::Read(buffer),
+            ])
+        }
+    }
+
     /// Basic function for sending the same byte of data (one u8) multiple times over spi
     ///
     /// Enables direct interaction with the device with the help of [command()](ConnectionInterface::command())
@@ -100,31 +364,42 @@ where
         val: u8,
         repetitions: u32,
     ) -> Result<(), SPI::Error> {
+        self.trace(TraceEvent::Repeat {
+            value: val,
+            count: repetitions,
+        });
+
         // high for data
         let _ = self.dc.set_high();
-        // Transfer data (u8) over spi
-        for _ in 0..repetitions {
-            self.write(spi, &[val])?;
+
+        // Transfer data (u8) over spi, from a small buffer instead of byte by byte
+        let buffer = [val; REPEAT_BUFFER_LEN];
+        let chunk_len = if SINGLE_BYTE_WRITE {
+            1
+        } else {
+            REPEAT_BUFFER_LEN.min(self.settings.max_transfer_len)
+        };
+        let mut remaining = repetitions as usize;
+        while remaining > 0 {
+            let len = remaining.min(chunk_len);
+            self.write(spi, &buffer[..len])?;
+            remaining -= len;
         }
         Ok(())
     }
 
     // spi write helper/abstraction function
     fn write(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
-        // transfer spi data
-        // Be careful!! Linux has a default limit of 4096 bytes per spi transfer
-        // see https://raspberrypi.stackexchange.com/questions/65595/spi-transfer-fails-with-buffer-size-greater-than-4096
-        if cfg!(target_os = "linux") {
-            for data_chunk in data.chunks(4096) {
-                spi.write(data_chunk)?;
-            }
-            Ok(())
-        } else if cfg!(feature = "simulator") {
+        if cfg!(feature = "simulator") {
             // No need to do in simulator
-            Ok(())
-        } else {
-            spi.write(data)
+            return Ok(());
         }
+
+        // transfer spi data, split up for platforms/DMA engines with a limit
+        for data_chunk in data.chunks(self.settings.max_transfer_len) {
+            spi.write(data_chunk)?;
+        }
+        Ok(())
     }
 
     /// Waits until device isn't busy anymore (busy == HIGH)
@@ -140,17 +415,12 @@ where
     ///
     /// Most likely there was a mistake with the 2in9 busy connection
     pub(crate) fn wait_until_idle(&mut self, delay: &mut DELAY, is_busy_low: bool) {
+        let mut polls = 0;
         while self.is_busy(is_busy_low) {
-            // This has been removed and added many time :
-            // - it is faster to not have it
-            // - it is complicated to pass the delay everywhere all the time
-            // - busy waiting can consume more power that delaying
-            // - delay waiting enables task switching on realtime OS
-            // -> keep it and leave the decision to the user
-            if self.delay_us > 0 {
-                delay.delay_us(self.delay_us);
-            }
+            self.busy_pause(delay, is_busy_low);
+            polls += 1;
         }
+        self.trace_busy(polls);
     }
 
     /// Same as `wait_until_idle` for device needing a command to probe Busy pin
@@ -165,15 +435,67 @@ where
         if self.delay_us > 0 {
             delay.delay_us(self.delay_us);
         }
+        let mut polls = 0;
         while self.is_busy(is_busy_low) {
             self.cmd(spi, status_command)?;
-            if self.delay_us > 0 {
-                delay.delay_us(self.delay_us);
-            }
+            self.busy_pause(delay, is_busy_low);
+            polls += 1;
         }
+        self.trace_busy(polls);
         Ok(())
     }
 
+    /// Traces the end of a busy loop with `polls` rounds
+    pub(crate) fn trace_busy(&self, polls: u32) {
+        let approx_us = if self.settings.busy_wait.is_some() {
+            None
+        } else {
+            Some(polls.saturating_mul(self.delay_us))
+        };
+        self.trace(TraceEvent::Busy { polls, approx_us });
+    }
+
+    /// Pauses one round of a busy loop
+    pub(crate) fn busy_pause(&mut self, delay: &mut DELAY, is_busy_low: bool) {
+        Self::pause(
+            &mut self.settings,
+            &mut self.busy,
+            delay,
+            self.delay_us,
+            is_busy_low,
+        );
+    }
+
+    /// Pauses one round of a busy loop on the BUSY pin of another controller
+    pub(crate) fn busy_pause_on(&mut self, busy: &mut BUSY, delay: &mut DELAY, is_busy_low: bool) {
+        Self::pause(&mut self.settings, busy, delay, self.delay_us, is_busy_low);
+    }
+
+    fn pause(
+        settings: &mut Settings<BUSY, RST>,
+        busy: &mut BUSY,
+        delay: &mut DELAY,
+        delay_us: u32,
+        is_busy_low: bool,
+    ) {
+        if let Some(busy_wait) = &mut settings.busy_wait {
+            let idle = if is_busy_low {
+                PinState::High
+            } else {
+                PinState::Low
+            };
+            busy_wait.wait(busy, idle);
+        } else if delay_us > 0 {
+            // This has been removed and added many time :
+            // - it is faster to not have it
+            // - it is complicated to pass the delay everywhere all the time
+            // - busy waiting can consume more power that delaying
+            // - delay waiting enables task switching on realtime OS
+            // -> keep it and leave the decision to the user
+            delay.delay_us(delay_us);
+        }
+    }
+
     /// Checks if device is still busy
     ///
     /// This is normally handled by the more complicated commands themselves,
@@ -197,22 +519,243 @@ where
         }
     }
 
-    /// Resets the device.
+    /// Resets the device at the start of its initialisation, with the display's
+    /// [ResetProfile] unless it's replaced
     ///
     /// Often used to awake the module from deep sleep. See [Epd4in2::sleep()](Epd4in2::sleep())
-    ///
-    /// The timing of keeping the reset pin low seems to be important and different per device.
-    /// Most displays seem to require keeping it low for 10ms, but the 7in5_v2 only seems to reset
-    /// properly with 2ms
-    pub(crate) fn reset(&mut self, delay: &mut DELAY, initial_delay: u32, duration: u32) {
+    pub(crate) fn init_reset(
+        &mut self,
+        delay: &mut DELAY,
+        profile: ResetProfile,
+        is_busy_low: bool,
+    ) {
+        let profile = self.settings.reset.unwrap_or(profile);
+        self.reset(delay, profile, is_busy_low);
+    }
+
+    /// Resets the device with the given [ResetProfile]
+    pub(crate) fn reset(&mut self, delay: &mut DELAY, profile: ResetProfile, is_busy_low: bool) {
+        self.power_on(delay);
+
+        self.trace(TraceEvent::Reset {
+            high_us: profile.initial_us,
+            low_us: profile.low_us,
+            wait_us: profile.wait_us,
+        });
+
         let _ = self.rst.set_high();
-        delay.delay_us(initial_delay);
+        delay.delay_us(profile.initial_us);
 
         let _ = self.rst.set_low();
-        delay.delay_us(duration);
+        delay.delay_us(profile.low_us);
         let _ = self.rst.set_high();
-        //TODO: the upstream libraries always sleep for 200ms here
-        // 10ms works fine with just for the 7in5_v2 but this needs to be validated for other devices
-        delay.delay_us(200_000);
+        delay.delay_us(profile.wait_us);
+
+        if profile.wait_for_busy {
+            self.wait_until_idle(delay, is_busy_low);
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "simulator")))]
+mod tests {
+    use super::*;
+    use crate::traits::DisplaySettings;
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        digital::{Mock as PinMock, State, Transaction as PinTransaction},
+        spi::{Mock as SpiMock, Transaction as SpiTransaction},
+    };
+
+    extern crate std;
+    use core::cell::Cell;
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use std::{boxed::Box, rc::Rc, vec, vec::Vec};
+
+    fn write(len: usize) -> Vec<SpiTransaction<u8>> {
+        vec![
+            SpiTransaction::transaction_start(),
+            SpiTransaction::write_vec(vec![0xFF; len]),
+            SpiTransaction::transaction_end(),
+        ]
+    }
+
+    #[test]
+    fn data_x_times_in_chunks() {
+        let expectations: Vec<_> = [write(256), write(256), write(88)].concat();
+        let mut spi = SpiMock::new(&expectations);
+        let mut dc = PinMock::new(&[PinTransaction::set(State::High)]);
+        let mut busy = PinMock::new(&[]);
+        let mut rst = PinMock::new(&[]);
+
+        let mut interface: DisplayInterface<_, _, _, _, NoopDelay, false> =
+            DisplayInterface::new(busy.clone(), dc.clone(), rst.clone(), None);
+        interface.data_x_times(&mut spi, 0xFF, 600).unwrap();
+
+        spi.done();
+        dc.done();
+        busy.done();
+        rst.done();
+    }
+
+    #[test]
+    fn data_x_times_respects_max_transfer_len() {
+        let expectations: Vec<_> = [write(100), write(100), write(56)].concat();
+        let mut spi = SpiMock::new(&expectations);
+        let mut dc = PinMock::new(&[PinTransaction::set(State::High)]);
+        let mut busy = PinMock::new(&[]);
+        let mut rst = PinMock::new(&[]);
+
+        let mut interface: DisplayInterface<_, _, _, _, NoopDelay, false> =
+            DisplayInterface::new(busy.clone(), dc.clone(), rst.clone(), None);
+        interface.set_max_transfer_len(100);
+        interface.data_x_times(&mut spi, 0xFF, 256).unwrap();
+
+        spi.done();
+        dc.done();
+        busy.done();
+        rst.done();
+    }
+
+    #[test]
+    fn power_pin_switched_in_sleep_and_reset() {
+        let mut dc = PinMock::new(&[PinTransaction::set(State::Low)]);
+        let mut busy = PinMock::new(&[]);
+        let mut rst = PinMock::new(&[
+            PinTransaction::set(State::Low),
+            PinTransaction::set(State::High),
+            PinTransaction::set(State::Low),
+            PinTransaction::set(State::High),
+        ]);
+        let mut power = PinMock::new(&[
+            PinTransaction::set(State::Low),
+            PinTransaction::set(State::High),
+        ]);
+
+        let mut interface: DisplayInterface<SpiMock<u8>, _, _, _, NoopDelay, false> =
+            DisplayInterface::new(busy.clone(), dc.clone(), rst.clone(), None);
+        let mut delay = NoopDelay::new();
+        assert!(interface
+            .set_power_pin(Some(PowerPin::new(power.clone(), 10_000, 1_000)))
+            .is_none());
+        interface.power_off(&mut delay);
+        interface.reset(&mut delay, ResetProfile::new(0, 2_000), false);
+
+        dc.done();
+        busy.done();
+        rst.done();
+        power.done();
+    }
+
+    fn busy_for_one_poll() -> PinMock {
+        PinMock::new(&[
+            PinTransaction::get(State::Low),
+            PinTransaction::get(State::High),
+        ])
+    }
+
+    #[test]
+    fn busy_wait_fn_gets_idle_level() {
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+        fn wait(_busy: &mut PinMock, idle: PinState) {
+            assert_eq!(idle, PinState::High);
+            CALLS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut busy = busy_for_one_poll();
+        let mut dc = PinMock::new(&[]);
+        let mut rst = PinMock::new(&[]);
+        let mut interface: DisplayInterface<SpiMock<u8>, _, _, _, NoopDelay, false> =
+            DisplayInterface::new(busy.clone(), dc.clone(), rst.clone(), None);
+        interface.set_busy_wait(Some(BusyWait::Fn(wait)));
+        interface.wait_until_idle(&mut NoopDelay::new(), true);
+
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+        dc.done();
+        busy.done();
+        rst.done();
+    }
+
+    #[test]
+    fn busy_wait_closure_keeps_state() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let wait = Box::leak(Box::new(move |idle: PinState| {
+            assert_eq!(idle, PinState::Low);
+            counter.set(counter.get() + 1);
+        }));
+
+        let mut busy = PinMock::new(&[
+            PinTransaction::get(State::High),
+            PinTransaction::get(State::High),
+            PinTransaction::get(State::Low),
+        ]);
+        let mut dc = PinMock::new(&[]);
+        let mut rst = PinMock::new(&[]);
+        let mut interface: DisplayInterface<SpiMock<u8>, _, _, _, NoopDelay, false> =
+            DisplayInterface::new(busy.clone(), dc.clone(), rst.clone(), None);
+        interface.set_busy_wait(Some(BusyWait::Closure(wait)));
+        interface.wait_until_idle(&mut NoopDelay::new(), false);
+
+        assert_eq!(calls.get(), 2);
+        dc.done();
+        busy.done();
+        rst.done();
+    }
+
+    #[test]
+    fn reset_profile_replaced() {
+        let mut dc = PinMock::new(&[]);
+        // only the replaced profile waits for BUSY
+        let mut busy = PinMock::new(&[PinTransaction::get(State::Low)]);
+        let mut rst = PinMock::new(&[
+            PinTransaction::set(State::High),
+            PinTransaction::set(State::Low),
+            PinTransaction::set(State::High),
+            PinTransaction::set(State::High),
+            PinTransaction::set(State::Low),
+            PinTransaction::set(State::High),
+        ]);
+
+        let mut interface: DisplayInterface<SpiMock<u8>, _, _, _, NoopDelay, false> =
+            DisplayInterface::new(busy.clone(), dc.clone(), rst.clone(), None);
+        interface.set_reset_profile(Some(ResetProfile::new(0, 2_000).with_busy_wait(0)));
+        interface.init_reset(&mut NoopDelay::new(), ResetProfile::new(0, 2_000), false);
+        // other resets keep the timing of the driver
+        interface.reset(&mut NoopDelay::new(), ResetProfile::new(0, 2_000), false);
+
+        dc.done();
+        busy.done();
+        rst.done();
+    }
+
+    #[test]
+    fn power_pin_replaced_while_off() {
+        let mut dc = PinMock::new(&[PinTransaction::set(State::Low)]);
+        let mut busy = PinMock::new(&[]);
+        let mut rst = PinMock::new(&[
+            PinTransaction::set(State::Low),
+            PinTransaction::set(State::High),
+            PinTransaction::set(State::Low),
+            PinTransaction::set(State::High),
+        ]);
+        let mut old = PinMock::new(&[PinTransaction::set(State::Low)]);
+        let mut new = PinMock::new(&[PinTransaction::set(State::High)]);
+
+        let mut interface: DisplayInterface<SpiMock<u8>, _, _, _, NoopDelay, false> =
+            DisplayInterface::new(busy.clone(), dc.clone(), rst.clone(), None);
+        let mut delay = NoopDelay::new();
+        interface.set_power_pin(Some(PowerPin::new(old.clone(), 0, 0)));
+        interface.power_off(&mut delay);
+        assert!(interface
+            .set_power_pin(Some(PowerPin::new(new.clone(), 0, 0)))
+            .is_some());
+        interface.reset(&mut delay, ResetProfile::new(0, 2_000), false);
+
+        dc.done();
+        busy.done();
+        rst.done();
+        old.done();
+        new.done();
     }
 }
@@ -0,0 +1,83 @@
+//! SPI Commands and Registers of the IT8951 timing controller
+
+/// IT8951 commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// Unlike the other controllers the commands are 16 bit words, sent after the
+/// command preamble.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Puts the controller into its running state (SYS_RUN)
+    SystemRun = 0x0001,
+    /// Standby, the clocks stay on (STANDBY)
+    Standby = 0x0002,
+    /// Sleep, the clocks are stopped (SLEEP)
+    Sleep = 0x0003,
+    /// Reads a register, the address follows as argument (REG_RD)
+    RegisterRead = 0x0010,
+    /// Writes a register, address and value follow as arguments (REG_WR)
+    RegisterWrite = 0x0011,
+    /// Starts loading an image of the full panel (LD_IMG)
+    LoadImage = 0x0020,
+    /// Starts loading an image area (LD_IMG_AREA)
+    ///
+    /// Arguments: settings, x, y, width and height
+    LoadImageArea = 0x0021,
+    /// Ends the image loading (LD_IMG_END)
+    LoadImageEnd = 0x0022,
+    /// Refreshes an area with the given waveform mode (DPY_AREA)
+    ///
+    /// Arguments: x, y, width, height and mode
+    DisplayArea = 0x0034,
+    /// Refreshes an area from a given image buffer address (DPY_BUF_AREA)
+    DisplayBufferArea = 0x0037,
+    /// Reads (argument 0) or writes (argument 1, value) the VCOM in mV
+    Vcom = 0x0039,
+    /// Reads the 20 words of device information
+    GetDeviceInfo = 0x0302,
+}
+
+impl Command {
+    /// Returns the 16 bit word of the command
+    pub(crate) fn address(self) -> u16 {
+        self as u16
+    }
+}
+
+/// IT8951 registers used by the driver
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Register {
+    /// Host interface control, 1 enables the packed write of the pixel data (I80CPCR)
+    PackedWrite = 0x0004,
+    /// Lower word of the image buffer address (LISAR)
+    ImageBufferAddressLow = 0x0208,
+    /// Higher word of the image buffer address (LISAR + 2)
+    ImageBufferAddressHigh = 0x020A,
+    /// LUT engine status, 0 once all refreshes are done (LUTAFSR)
+    LutStatus = 0x1224,
+}
+
+impl Register {
+    /// Returns the 16 bit address of the register
+    pub(crate) fn address(self) -> u16 {
+        self as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::SystemRun.address(), 0x0001);
+        assert_eq!(Command::LoadImageArea.address(), 0x0021);
+        assert_eq!(Command::DisplayArea.address(), 0x0034);
+        assert_eq!(Command::GetDeviceInfo.address(), 0x0302);
+        assert_eq!(Register::ImageBufferAddressHigh.address(), 0x020A);
+        assert_eq!(Register::LutStatus.address(), 0x1224);
+    }
+}
@@ -0,0 +1,627 @@
+//! A Driver for the Waveshare e-Paper HATs with an IT8951 timing controller via SPI
+//!
+//! The 6", 7.8", 9.7" and 10.3" HAT panels aren't driven directly, an IT8951 controller
+//! with its own frame memory sits in between. It speaks a protocol of its own: every SPI
+//! transfer starts with a 16 bit preamble, there is no DC line and the controller signals
+//! with its HRDY line (wired to BUSY, low while busy) when it accepts the next transfer.
+//!
+//! The resolution is read from the controller, so the images are drawn on a
+//! [`VarDisplay`](crate::graphics::VarDisplay) of [`Gray16Color`]. Such a buffer can
+//! cover the whole panel or just an area of it, which is then loaded and refreshed on its
+//! own with a [`WaveformMode`]: [`Du`](WaveformMode::Du) for fast black and white
+//! changes, [`Gc16`](WaveformMode::Gc16) for all 16 grey levels.
+//!
+//! The VCOM voltage differs from panel to panel, it's printed on the FPC cable
+//! (e.g. `-1.50V`) and given to [`It8951::new`] in mV without the sign (`1500`).
+//!
+//! The driver has its own transport instead of the display interface of the other
+//! drivers, so it doesn't implement [`DisplaySettings`]: busy waits, traces, transfer
+//! limits and power pins have no counterpart here. HRDY is polled between the transfers
+//! and the pixel data is always sent in chunks of 256 bytes.
+//!
+//! [`DisplaySettings`]: crate::traits::DisplaySettings
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::eh1::*;
+//!# fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+//!use embedded_graphics::{prelude::*, primitives::{Circle, PrimitiveStyle}};
+//!use epd_waveshare::{graphics::VarDisplay, it8951::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let busy_in = digital::Mock::new(&expectations);
+//!# let rst = digital::Mock::new(&expectations);
+//!# let mut delay = delay::NoopDelay::new();
+//!
+//!// Setup the controller with a VCOM of -1.50V
+//!let mut epd = It8951::new(&mut spi, busy_in, rst, &mut delay, None, 1500)?;
+//!epd.clear_frame(&mut spi, &mut delay)?;
+//!
+//!// Draw into an area of 200x200 pixels
+//!let mut buffer = [0u8; 200 * 200 / 2];
+//!let mut display = VarDisplay::<Gray16Color>::new(200, 200, &mut buffer, false)
+//!    .expect("buffer too small");
+//!display.clear(Gray16Color::WHITE).ok();
+//!let _ = Circle::new(Point::new(50, 50), 100)
+//!    .into_styled(PrimitiveStyle::with_fill(Gray16Color::new(0x8)))
+//!    .draw(&mut display);
+//!
+//!// and show it at (96, 40)
+//!epd.update_and_display_area(
+//!    &mut spi,
+//!    display.buffer(),
+//!    96,
+//!    40,
+//!    200,
+//!    200,
+//!    WaveformMode::Gc16,
+//!    &mut delay,
+//!)?;
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+//!
+//! # References
+//!
+//! - [Datasheet](https://www.waveshare.com/wiki/10.3inch_e-Paper_HAT)
+//! - [Waveshare C driver](https://github.com/waveshare/IT8951-ePaper)
+
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::{Operation, SpiDevice},
+};
+
+pub(crate) mod command;
+use self::command::{Command, Register};
+
+use crate::color::Gray16Color;
+
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Gray16Color = Gray16Color::WHITE;
+
+/// Preamble of a command transfer
+const PREAMBLE_COMMAND: u16 = 0x6000;
+/// Preamble of a data write
+const PREAMBLE_WRITE: u16 = 0x0000;
+/// Preamble of a data read
+const PREAMBLE_READ: u16 = 0x1000;
+
+/// Number of words of the device information
+const DEVICE_INFO_WORDS: usize = 20;
+/// Bits per pixel setting of [`Command::LoadImageArea`] for 4 bits per pixel
+const LOAD_IMAGE_4BPP: u16 = 2 << 4;
+/// Bytes sent per pixel data transfer
+const CHUNK_LEN: usize = 256;
+/// Pause between the phases of a transfer, in ns
+///
+/// The Waveshare C driver waits for HRDY after the preamble and after the dummy bytes of
+/// a read. The pin can't be polled within a [`SpiDevice`] transaction, so the driver
+/// pauses instead. The controller is ready again after a few µs.
+const PHASE_DELAY_NS: u32 = 10_000;
+
+/// Waveform modes of the IT8951
+///
+/// The numbers of the modes depend on the LUT of the panel, the driver picks the right
+/// one for [`A2`](WaveformMode::A2).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WaveformMode {
+    /// Flashes the area to white, used to clear the panel
+    Init,
+    /// Fast update of black and white changes, without flashing
+    Du,
+    /// Flashing update with all 16 grey levels
+    Gc16,
+    /// Fastest black and white update, for animations
+    ///
+    /// Ghosting builds up, clear the panel with [`Init`](WaveformMode::Init) now and then.
+    A2,
+}
+
+/// The information the IT8951 reports about itself and its panel
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DeviceInfo {
+    /// Width of the panel
+    pub width: u16,
+    /// Height of the panel
+    pub height: u16,
+    /// Address of the image buffer in the memory of the controller
+    pub image_buffer_address: u32,
+    /// Firmware version as zero terminated ASCII string
+    pub firmware_version: [u8; 16],
+    /// LUT version as zero terminated ASCII string, e.g. `M641`
+    pub lut_version: [u8; 16],
+}
+
+impl DeviceInfo {
+    fn from_words(words: &[u16; DEVICE_INFO_WORDS]) -> Self {
+        // Strings are stored like the C driver does on its little endian host
+        let mut firmware_version = [0; 16];
+        let mut lut_version = [0; 16];
+        for (i, word) in words[4..12].iter().enumerate() {
+            firmware_version[i * 2..i * 2 + 2].copy_from_slice(&word.to_le_bytes());
+        }
+        for (i, word) in words[12..20].iter().enumerate() {
+            lut_version[i * 2..i * 2 + 2].copy_from_slice(&word.to_le_bytes());
+        }
+
+        DeviceInfo {
+            width: words[0],
+            height: words[1],
+            image_buffer_address: u32::from(words[2]) | u32::from(words[3]) << 16,
+            firmware_version,
+            lut_version,
+        }
+    }
+
+    /// Panels with the `M641` LUT (the 6" HAT) use mode 4 for A2 and need the areas
+    /// aligned to 4 bytes
+    fn is_m641(&self) -> bool {
+        self.lut_version.starts_with(b"M641\0")
+    }
+}
+
+/// It8951 driver
+///
+/// Unlike the other drivers this one doesn't implement
+/// [`WaveshareDisplay`](crate::traits::WaveshareDisplay), as the controller has neither a
+/// DC pin nor a fixed resolution. The inherent methods mirror that trait instead.
+pub struct It8951<SPI, BUSY, RST, DELAY> {
+    _spi: PhantomData<SPI>,
+    _delay: PhantomData<DELAY>,
+    /// HRDY, low while the controller is busy
+    busy: BUSY,
+    /// Pin for Resetting
+    rst: RST,
+    /// number of us the idle loops sleep on
+    delay_us: u32,
+    /// Device information read during the initialisation
+    info: DeviceInfo,
+    /// VCOM in mV
+    vcom: u16,
+}
+
+impl<SPI, BUSY, RST, DELAY> It8951<SPI, BUSY, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Creates a new driver from a SPI peripheral, HRDY as BUSY InputPin and RST
+    ///
+    /// `vcom` is the VCOM voltage of the panel in mV, without the sign.
+    /// If no delay is given, a default delay of 10ms is used for the busy loops.
+    ///
+    /// This already initialises the device.
+    pub fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+        vcom: u16,
+    ) -> Result<Self, SPI::Error> {
+        let mut epd = It8951 {
+            _spi: PhantomData,
+            _delay: PhantomData,
+            busy,
+            rst,
+            delay_us: delay_us.unwrap_or(10_000),
+            info: DeviceInfo::from_words(&[0; DEVICE_INFO_WORDS]),
+            vcom,
+        };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.reset(delay);
+
+        self.command(spi, delay, Command::SystemRun)?;
+
+        self.command(spi, delay, Command::GetDeviceInfo)?;
+        let mut words = [0; DEVICE_INFO_WORDS];
+        self.read_words(spi, delay, &mut words)?;
+        self.info = DeviceInfo::from_words(&words);
+
+        // Pixel data is written packed, not one pixel per word
+        self.write_register(spi, delay, Register::PackedWrite, 0x0001)?;
+
+        self.set_vcom(spi, delay, self.vcom)
+    }
+
+    /// Wakes the controller up from [`sleep`](Self::sleep)
+    pub fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.command(spi, delay, Command::SystemRun)
+    }
+
+    /// Puts the controller to sleep once all refreshes are done
+    ///
+    /// The panel keeps its image, [`wake_up`](Self::wake_up) brings the controller back.
+    pub fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_display_ready(spi, delay)?;
+        self.command(spi, delay, Command::Sleep)
+    }
+
+    /// Information about the controller and its panel
+    pub fn device_info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
+    /// Get the width of the display
+    pub fn width(&self) -> u32 {
+        u32::from(self.info.width)
+    }
+
+    /// Get the height of the display
+    pub fn height(&self) -> u32 {
+        u32::from(self.info.height)
+    }
+
+    /// Pixels the x position and the width of an area have to be a multiple of
+    ///
+    /// Four pixels fill one word of the controller, the `M641` panels need four bytes.
+    pub fn alignment(&self) -> u32 {
+        if self.info.is_m641() {
+            8
+        } else {
+            4
+        }
+    }
+
+    /// Sets the VCOM voltage in mV, without the sign
+    pub fn set_vcom(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        vcom: u16,
+    ) -> Result<(), SPI::Error> {
+        self.command(spi, delay, Command::Vcom)?;
+        self.write_args(spi, delay, &[0x0001, vcom])?;
+        self.vcom = vcom;
+        Ok(())
+    }
+
+    /// Reads the VCOM voltage in mV, without the sign, from the controller
+    pub fn get_vcom(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<u16, SPI::Error> {
+        self.command(spi, delay, Command::Vcom)?;
+        self.write_args(spi, delay, &[0x0000])?;
+        let mut vcom = [0];
+        self.read_words(spi, delay, &mut vcom)?;
+        Ok(vcom[0])
+    }
+
+    /// Loads an area of the panel into the memory of the controller, without refreshing it
+    ///
+    /// `buffer` holds the `width` x `height` pixels of the area with 4 bits per pixel, as
+    /// provided by a [`VarDisplay`](crate::graphics::VarDisplay) of [`Gray16Color`].
+    /// `x` and `width` have to be a multiple of [`alignment`](Self::alignment).
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_area(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.check_area(x, y, width, height);
+        let line_bytes = width as usize / 2;
+        assert!(buffer.len() >= line_bytes * height as usize);
+
+        self.load_image_area_start(spi, delay, x, y, width, height)?;
+        let mut chunk = [0; CHUNK_LEN];
+        for line in buffer.chunks(line_bytes).take(height as usize) {
+            for data in line.chunks(CHUNK_LEN) {
+                let packed = &mut chunk[..data.len()];
+                pack_pixels(data, packed);
+                self.send(spi, delay, PREAMBLE_WRITE, packed)?;
+            }
+        }
+        self.command(spi, delay, Command::LoadImageEnd)
+    }
+
+    /// Refreshes an area of the panel from the memory of the controller
+    ///
+    /// This doesn't wait for the refresh, the next load waits until the controller is
+    /// done with it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn display_area(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        mode: WaveformMode,
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.check_area(x, y, width, height);
+        self.command(spi, delay, Command::DisplayArea)?;
+        self.write_args(
+            spi,
+            delay,
+            &[
+                x as u16,
+                y as u16,
+                width as u16,
+                height as u16,
+                self.waveform(mode),
+            ],
+        )
+    }
+
+    /// Loads and refreshes an area of the panel, see [`update_area`](Self::update_area)
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_and_display_area(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        mode: WaveformMode,
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_area(spi, buffer, x, y, width, height, delay)?;
+        self.display_area(spi, x, y, width, height, mode, delay)
+    }
+
+    /// Loads and refreshes the whole panel
+    pub fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        mode: WaveformMode,
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        let (width, height) = (self.width(), self.height());
+        self.update_and_display_area(spi, buffer, 0, 0, width, height, mode, delay)
+    }
+
+    /// Clears the whole panel to white with the [`Init`](WaveformMode::Init) mode
+    pub fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let (width, height) = (self.width(), self.height());
+        let white = DEFAULT_BACKGROUND_COLOR.luma() * 0x11;
+
+        self.load_image_area_start(spi, delay, 0, 0, width, height)?;
+        let chunk = [white; CHUNK_LEN];
+        let mut remaining = width as usize / 2 * height as usize;
+        while remaining > 0 {
+            let len = remaining.min(CHUNK_LEN);
+            self.send(spi, delay, PREAMBLE_WRITE, &chunk[..len])?;
+            remaining -= len;
+        }
+        self.command(spi, delay, Command::LoadImageEnd)?;
+
+        self.display_area(spi, 0, 0, width, height, WaveformMode::Init, delay)
+    }
+
+    /// Waits until the controller accepts the next transfer (HRDY is high)
+    pub fn wait_until_idle(&mut self, delay: &mut DELAY) {
+        while self.busy.is_low().unwrap_or(false) {
+            if self.delay_us > 0 {
+                delay.delay_us(self.delay_us);
+            }
+        }
+    }
+
+    /// Waits until all refreshes of the panel are done
+    pub fn wait_until_display_ready(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        while self.read_register(spi, delay, Register::LutStatus)? != 0 {
+            if self.delay_us > 0 {
+                delay.delay_us(self.delay_us);
+            }
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self, delay: &mut DELAY) {
+        let _ = self.rst.set_high();
+        delay.delay_us(200_000);
+        let _ = self.rst.set_low();
+        delay.delay_us(10_000);
+        let _ = self.rst.set_high();
+        delay.delay_us(200_000);
+    }
+
+    fn waveform(&self, mode: WaveformMode) -> u16 {
+        match mode {
+            WaveformMode::Init => 0,
+            WaveformMode::Du => 1,
+            WaveformMode::Gc16 => 2,
+            WaveformMode::A2 if self.info.is_m641() => 4,
+            WaveformMode::A2 => 6,
+        }
+    }
+
+    fn check_area(&self, x: u32, y: u32, width: u32, height: u32) {
+        let align = self.alignment();
+        assert!(x % align == 0 && width % align == 0);
+        assert!(x + width <= self.width() && y + height <= self.height());
+    }
+
+    fn load_image_area_start(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_display_ready(spi, delay)?;
+
+        let address = self.info.image_buffer_address;
+        self.write_register(
+            spi,
+            delay,
+            Register::ImageBufferAddressHigh,
+            (address >> 16) as u16,
+        )?;
+        self.write_register(spi, delay, Register::ImageBufferAddressLow, address as u16)?;
+
+        self.command(spi, delay, Command::LoadImageArea)?;
+        self.write_args(
+            spi,
+            delay,
+            &[
+                LOAD_IMAGE_4BPP,
+                x as u16,
+                y as u16,
+                width as u16,
+                height as u16,
+            ],
+        )
+    }
+
+    fn write_register(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        register: Register,
+        value: u16,
+    ) -> Result<(), SPI::Error> {
+        self.command(spi, delay, Command::RegisterWrite)?;
+        self.write_args(spi, delay, &[register.address(), value])
+    }
+
+    fn read_register(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        register: Register,
+    ) -> Result<u16, SPI::Error> {
+        self.command(spi, delay, Command::RegisterRead)?;
+        self.write_args(spi, delay, &[register.address()])?;
+        let mut value = [0];
+        self.read_words(spi, delay, &mut value)?;
+        Ok(value[0])
+    }
+
+    fn command(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        command: Command,
+    ) -> Result<(), SPI::Error> {
+        self.send(
+            spi,
+            delay,
+            PREAMBLE_COMMAND,
+            &command.address().to_be_bytes(),
+        )
+    }
+
+    fn write_args(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        args: &[u16],
+    ) -> Result<(), SPI::Error> {
+        for arg in args {
+            self.send(spi, delay, PREAMBLE_WRITE, &arg.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn read_words(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        words: &mut [u16],
+    ) -> Result<(), SPI::Error> {
+        let mut data = [0; DEVICE_INFO_WORDS * 2];
+        let data = &mut data[..words.len() * 2];
+        // Two dummy bytes come before the data
+        let mut dummy = [0; 2];
+
+        self.wait_until_idle(delay);
+        spi.transaction(&mut [
+            Operation::Write(&PREAMBLE_READ.to_be_bytes()),
+            Operation::DelayNs(PHASE_DELAY_NS),
+            Operation::Read(&mut dummy),
+            Operation::DelayNs(PHASE_DELAY_NS),
+            Operation::Read(data),
+        ])?;
+
+        for (word, bytes) in words.iter_mut().zip(data.chunks(2)) {
+            *word = u16::from_be_bytes([bytes[0], bytes[1]]);
+        }
+        Ok(())
+    }
+
+    fn send(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        preamble: u16,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(delay);
+        spi.transaction(&mut [
+            Operation::Write(&preamble.to_be_bytes()),
+            Operation::DelayNs(PHASE_DELAY_NS),
+            Operation::Write(data),
+        ])
+    }
+}
+
+/// Reorders the pixels of a [`Gray16Color`] buffer for the controller
+///
+/// The buffer has the first pixel in the high nibble, the controller expects the first
+/// of the four pixels of a word in its lowest bits, with the word sent high byte first.
+fn pack_pixels(src: &[u8], dst: &mut [u8]) {
+    for (s, d) in src.chunks(2).zip(dst.chunks_mut(2)) {
+        d[0] = s[1].rotate_left(4);
+        d[1] = s[0].rotate_left(4);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_packing() {
+        let mut packed = [0; 4];
+        pack_pixels(&[0x01, 0x23, 0x45, 0x67], &mut packed);
+        assert_eq!(packed, [0x32, 0x10, 0x76, 0x54]);
+    }
+
+    #[test]
+    fn device_info() {
+        let mut words = [0; DEVICE_INFO_WORDS];
+        words[0] = 1448;
+        words[1] = 1072;
+        words[2] = 0x36E0;
+        words[3] = 0x0011;
+        words[12] = u16::from_le_bytes(*b"M6");
+        words[13] = u16::from_le_bytes(*b"41");
+
+        let info = DeviceInfo::from_words(&words);
+        assert_eq!(info.width, 1448);
+        assert_eq!(info.height, 1072);
+        assert_eq!(info.image_buffer_address, 0x0011_36E0);
+        assert!(info.is_m641());
+
+        words[13] = u16::from_le_bytes(*b"42");
+        assert!(!DeviceInfo::from_words(&words).is_m641());
+    }
+}
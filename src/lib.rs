@@ -56,13 +56,29 @@
 //!
 //! ### SPI
 //!
-//! MISO is not connected/available. SPI_MODE_0 is used (CPHL = 0, CPOL = 0) with 8 bits per word, MSB first.
+//! MISO is not needed. SPI_MODE_0 is used (CPHL = 0, CPOL = 0) with 8 bits per word, MSB first.
+//!
+//! Register reads like `Epd7in5::read_temperature` of the
+//! [`epd7in5_yrd0750ryf665f60`] driver need either a connected MISO line or a [`SpiDevice`]
+//! which turns the bidirectional SDA line of the display around for the read phase
+//! (3-wire half duplex). The read command and the read phase share one transaction.
+//!
+//! [`SpiDevice`]: embedded_hal::spi::SpiDevice
+//!
+//! Boards without a DC pin can drive controllers strapped to 3-wire SPI through
+//! [`three_wire::ThreeWireSpi`].
 //!
 //! Maximum speed tested by myself was 8Mhz but more should be possible (Ben Krasnow used 18Mhz with his implemenation)
 //!
+//! Long writes are split into transfers of at most [`DEFAULT_MAX_TRANSFER_LEN`] bytes, this can
+//! be changed per display with `DisplaySettings::set_max_transfer_len`.
+//!
 #![cfg_attr(not(feature = "simulator"), no_std)]
 #![deny(missing_docs)]
 
+#[macro_use]
+mod fmt;
+
 #[cfg(feature = "graphics")]
 pub mod graphics;
 
@@ -74,16 +90,45 @@ pub mod rect;
 
 /// Interface for the physical connection between display and the controlling device
 mod interface;
+pub use crate::interface::{BusyWait, PowerPin, ResetProfile, DEFAULT_MAX_TRANSFER_LEN};
+
+pub mod trace;
 
+pub mod three_wire;
+
+pub mod epd12in48;
+pub mod epd13in3e;
+pub mod epd1in54;
+pub mod epd1in64g;
+pub mod epd2in36g;
+pub mod epd2in66b;
+pub mod epd2in7_v2;
+pub mod epd2in9;
+pub mod epd2in9_v2;
+pub mod epd3in0g;
+pub mod epd3in7;
+pub mod epd4in2;
+pub mod epd4in37g;
+pub mod epd5in65f;
+pub mod epd5in83b_v2;
+pub mod epd7in3f;
+pub mod epd7in3g;
+pub mod epd7in5_hd;
 pub mod epd7in5_yrd0750ryf665f60;
+pub mod epd7in5b_v2;
+pub mod it8951;
+pub mod ssd1680;
 
-// pub(crate) mod type_a;
+pub(crate) mod type_a;
+pub mod type_g;
 
 /// Includes everything important besides the chosen Display
 pub mod prelude {
-    pub use crate::color::{Color, OctColor, TriColor};
+    pub use crate::color::{
+        Color, Gray16Color, Gray4Color, HexColor, OctColor, QuadColor, TriColor,
+    };
     pub use crate::traits::{
-        QuickRefresh, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+        DisplaySettings, QuickRefresh, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
     };
 
     pub use crate::SPI_MODE;
@@ -2,6 +2,7 @@
 use core::cmp;
 
 /// A rectangle
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
 pub struct Rect {
     /// Origin X
@@ -0,0 +1,83 @@
+//! SPI Commands of the SSD1680 and SSD1681 controllers
+
+use crate::traits;
+
+/// SSD1680 and SSD1681 commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// For more infos about the addresses and what they are doing look into the SSD1680 datasheet.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Gate setting, number of lines and scanning order
+    DriverOutputControl = 0x01,
+    /// Set gate driving voltage
+    GateDrivingVoltage = 0x03,
+    /// Set source driving voltage
+    SourceDrivingVoltage = 0x04,
+    /// Deep sleep mode
+    ///
+    /// 0x01: mode 1, RAM is retained
+    DeepSleepMode = 0x10,
+    /// Define data entry sequence, X/Y increment or decrement
+    DataEntryModeSetting = 0x11,
+    /// Resets the commands and parameters to their S/W Reset default values except R10h-Deep Sleep Mode.
+    /// During operation, BUSY pad will output high.
+    SwReset = 0x12,
+    /// Selects the internal or an external temperature sensor
+    TemperatureSensorControl = 0x18,
+    /// Activate the display update sequence selected with [`Command::DisplayUpdateControl2`]
+    MasterActivation = 0x20,
+    /// RAM content option for the display update
+    DisplayUpdateControl1 = 0x21,
+    /// Display update sequence option
+    ///
+    /// - 0xF7 / 0xFF: display mode 1 / 2 with the waveform loaded from the OTP
+    /// - 0xC7 / 0xCF: display mode 1 / 2 with the LUT written by [`Command::WriteLutRegister`]
+    DisplayUpdateControl2 = 0x22,
+    /// Write the black/white RAM, the new frame of a partial refresh
+    WriteRam = 0x24,
+    /// Write the second RAM, the red plane of tri-color glass or the old frame of a
+    /// partial refresh
+    WriteRam2 = 0x26,
+    /// Write VCOM register from MCU interface
+    WriteVcomRegister = 0x2C,
+    /// Write LUT register from MCU interface (153 bytes)
+    WriteLutRegister = 0x32,
+    /// Display option, enables the ping-pong mode of the two RAMs
+    WriteDisplayOption = 0x37,
+    /// Select border waveform for VBD
+    BorderWaveformControl = 0x3C,
+    /// Last byte of the LUT, the EOPT (end option) setting
+    WriteLutRegisterEnd = 0x3F,
+    /// Specify the start/end positions of the window address in the X direction
+    SetRamXAddressStartEndPosition = 0x44,
+    /// Specify the start/end positions of the window address in the Y direction
+    SetRamYAddressStartEndPosition = 0x45,
+    /// Make initial settings for the RAM X address in the address counter (AC)
+    SetRamXAddressCounter = 0x4E,
+    /// Make initial settings for the RAM Y address in the address counter (AC)
+    SetRamYAddressCounter = 0x4F,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::DriverOutputControl.address(), 0x01);
+        assert_eq!(Command::WriteRam2.address(), 0x26);
+        assert_eq!(Command::WriteLutRegisterEnd.address(), 0x3F);
+        assert_eq!(Command::SetRamYAddressCounter.address(), 0x4F);
+    }
+}
@@ -0,0 +1,576 @@
+//! A controller level Driver for displays with a SSD1680 or SSD1681 via SPI
+//!
+//! Many black/white and black/white/red panels only differ in their resolution and
+//! waveform. The resolution and an optional LUT are passed at runtime with a [`Config`],
+//! so the frame buffer is usually a [`VarDisplay`](crate::graphics::VarDisplay).
+//!
+//! Without a LUT the waveforms from the OTP of the glass are used, [`RefreshLut::Quick`]
+//! then selects the second display mode, which is the fast one on most panels.
+//!
+//! For black/white/red glass draw with `TriColor` on a `VarDisplay` created with
+//! `bwrbit = true` and send both halves with
+//! [`WaveshareThreeColorDisplay::update_color_frame`]. A set bit in the chromatic half
+//! shows red.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::eh1::*;
+//!# fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+//!use embedded_graphics::{
+//!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{buffer_len, graphics::VarDisplay, prelude::*, ssd1680::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let busy_in = digital::Mock::new(&expectations);
+//!# let dc = digital::Mock::new(&expectations);
+//!# let rst = digital::Mock::new(&expectations);
+//!# let mut delay = delay::NoopDelay::new();
+//!
+//!// Setup EPD for a 2.13" panel
+//!let config = Config::new(122, 250);
+//!let mut epd = Ssd1680::with_config(&mut spi, busy_in, dc, rst, &mut delay, None, config)?;
+//!
+//!// The buffer for VarDisplay
+//!let mut buffer = [0u8; buffer_len(122, 250)];
+//!let mut display = VarDisplay::<Color>::new(epd.width(), epd.height(), &mut buffer, false)
+//!    .expect("buffer too small");
+//!
+//!let _ = Line::new(Point::new(0, 120), Point::new(0, 249))
+//!    .into_styled(PrimitiveStyle::with_stroke(Color::Black, 1))
+//!    .draw(&mut display);
+//!
+//!epd.update_and_display_frame(&mut spi, display.buffer(), &mut delay)?;
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+//!
+//! The Waveshare panels with these controllers, e.g. [`epd2in9_v2`](crate::epd2in9_v2),
+//! have their own modules with the tuned settings of the sample code.
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+use crate::traits::{
+    InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+};
+
+pub(crate) mod command;
+use self::command::Command;
+
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+/// Length of a LUT without the voltage settings
+pub const LUT_LEN: usize = 153;
+/// Length of a LUT in the layout of the Waveshare sample code
+///
+/// The LUT is followed by EOPT, VGH, VSH1, VSH2, VSL and VCOM.
+pub const LUT_WITH_VOLTAGES_LEN: usize = LUT_LEN + 6;
+const IS_BUSY_LOW: bool = false;
+/// Reset pulse, the controller signals the end of its reset on BUSY
+const RESET: ResetProfile = ResetProfile::new(10_000, 2_000).with_busy_wait(10_000);
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// A custom waveform, with or without the voltage settings
+///
+/// The bytes are copied, so the LUT can be loaded at runtime, e.g. from a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lut {
+    bytes: [u8; LUT_WITH_VOLTAGES_LEN],
+    len: usize,
+}
+
+impl Lut {
+    /// Copies a LUT of [`LUT_LEN`] or [`LUT_WITH_VOLTAGES_LEN`] bytes
+    ///
+    /// Returns `None` for other lengths.
+    pub fn new(lut: &[u8]) -> Option<Self> {
+        if lut.len() != LUT_LEN && lut.len() != LUT_WITH_VOLTAGES_LEN {
+            return None;
+        }
+        let mut bytes = [0; LUT_WITH_VOLTAGES_LEN];
+        bytes[..lut.len()].copy_from_slice(lut);
+        Some(Lut {
+            bytes,
+            len: lut.len(),
+        })
+    }
+
+    /// The bytes of the LUT
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+/// Panel specific settings of the [`Ssd1680`] driver
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Width of the glass, the number of sources used
+    pub width: u32,
+    /// Height of the glass, the number of gates used
+    pub height: u32,
+    /// Custom waveform
+    ///
+    /// `None` uses the waveforms from the OTP.
+    pub lut: Option<Lut>,
+}
+
+impl Config {
+    /// Settings for a panel of the given size, using the waveforms from the OTP
+    pub const fn new(width: u32, height: u32) -> Self {
+        Config {
+            width,
+            height,
+            lut: None,
+        }
+    }
+
+    /// Uses a custom waveform instead of the OTP
+    pub const fn with_lut(self, lut: Lut) -> Self {
+        Config {
+            lut: Some(lut),
+            ..self
+        }
+    }
+}
+
+impl Default for Config {
+    /// A 128×296 panel, the 2.9" glass most SSD1680 modules carry
+    fn default() -> Self {
+        Config::new(128, 296)
+    }
+}
+
+/// SSD1680 / SSD1681 driver
+///
+pub struct Ssd1680<SPI, BUSY, DC, RST, DELAY> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Background Color
+    color: Color,
+    /// Refresh LUT
+    refresh: RefreshLut,
+    /// Panel settings
+    config: Config,
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Reset the device
+        self.interface.init_reset(delay, RESET, IS_BUSY_LOW);
+
+        self.command(spi, Command::SwReset)?;
+        self.wait_until_idle(spi, delay)?;
+
+        let gates = self.config.height - 1;
+        self.cmd_with_data(
+            spi,
+            Command::DriverOutputControl,
+            &[gates as u8, (gates >> 8) as u8, 0x00],
+        )?;
+        // X increment, Y increment
+        self.cmd_with_data(spi, Command::DataEntryModeSetting, &[0x03])?;
+        self.set_ram_area(spi)?;
+        self.cmd_with_data(spi, Command::BorderWaveformControl, &[0x05])?;
+        self.cmd_with_data(spi, Command::DisplayUpdateControl1, &[0x00, 0x80])?;
+        self.cmd_with_data(spi, Command::TemperatureSensorControl, &[0x80])?;
+
+        if let Some(lut) = self.config.lut {
+            self.write_lut(spi, delay, lut.as_bytes())?;
+        }
+
+        self.set_ram_counter(spi)?;
+        self.wait_until_idle(spi, delay)
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn update_color_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        black: &[u8],
+        chromatic: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.update_achromatic_frame(spi, delay, black)?;
+        self.update_chromatic_frame(spi, delay, chromatic)
+    }
+
+    /// Update only the black/white data of the display.
+    ///
+    /// A set bit is a white pixel, a cleared bit a black one.
+    fn update_achromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        black: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.write_ram(spi, delay, Command::WriteRam, black)
+    }
+
+    /// Update only the chromatic data of the display.
+    ///
+    /// Every set bit is drawn red.
+    fn update_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        chromatic: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.write_ram(spi, delay, Command::WriteRam2, chromatic)
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = Color;
+
+    /// Creates a driver with [`Config::default`], use [`Ssd1680::with_config`] for
+    /// other panels
+    fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        Self::with_config(spi, busy, dc, rst, delay, delay_us, Config::default())
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.write_ram(spi, delay, Command::WriteRam, buffer)
+    }
+
+    /// Updates a part of the frame
+    ///
+    /// `x` and `width` have to be multiples of 8, as the RAM is addressed in whole bytes
+    /// in X direction.
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        assert!(width > 0 && x + width <= self.config.width);
+        assert!(height > 0 && y + height <= self.config.height);
+
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_window(spi, x, y, x + width - 1, y + height - 1)?;
+        self.set_ram_position(spi, x, y)?;
+        self.cmd_with_data(spi, Command::WriteRam, buffer)?;
+
+        // back to the full frame for the other transfers
+        self.set_ram_area(spi)
+    }
+
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let sequence = display_sequence(self.config.lut.is_some(), self.refresh);
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DisplayUpdateControl2, &[sequence])?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    /// Fills the black/white RAM with the background color and clears the red RAM
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        let bg = self.color.get_byte_value();
+        let len = buffer_len(self.config.width as usize, self.config.height as usize) as u32;
+
+        self.wait_until_idle(spi, delay)?;
+        for (command, value) in [(Command::WriteRam, bg), (Command::WriteRam2, 0x00)] {
+            self.set_ram_counter(spi)?;
+            self.command(spi, command)?;
+            self.interface.data_x_times(spi, value, len)?;
+        }
+
+        self.display_frame(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        self.config.width
+    }
+
+    fn height(&self) -> u32 {
+        self.config.height
+    }
+
+    /// Selects display mode 1 ([`RefreshLut::Full`]) or 2 ([`RefreshLut::Quick`]) for the
+    /// next refreshes
+    ///
+    /// Rewrites the custom LUT of the [`Config`], if there is one.
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), SPI::Error> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        match self.config.lut {
+            Some(lut) => self.write_lut(spi, delay, lut.as_bytes()),
+            None => Ok(()),
+        }
+    }
+
+    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        Ok(())
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST> for Ssd1680<SPI, BUSY, DC, RST, DELAY> {
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+impl<SPI, BUSY, DC, RST, DELAY> Ssd1680<SPI, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Creates a driver for the panel described by `config`
+    ///
+    /// # Panics
+    ///
+    /// If the width or the height of the `config` is zero.
+    pub fn with_config(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+        config: Config,
+    ) -> Result<Self, SPI::Error> {
+        assert!(config.width > 0 && config.height > 0);
+
+        let interface = DisplayInterface::new(busy, dc, rst, delay_us);
+        let mut epd = Ssd1680 {
+            interface,
+            color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            config,
+        };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    /// Returns the panel settings
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
+        self.interface.cmd(spi, command)
+    }
+
+    fn cmd_with_data(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        data: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.interface.cmd_with_data(spi, command, data)
+    }
+
+    fn write_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        lut: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.cmd_with_data(spi, Command::WriteLutRegister, &lut[..LUT_LEN])?;
+        self.wait_until_idle(spi, delay)?;
+
+        if let [eopt, vgh, vsh1, vsh2, vsl, vcom] = lut[LUT_LEN..] {
+            self.cmd_with_data(spi, Command::WriteLutRegisterEnd, &[eopt])?;
+            self.cmd_with_data(spi, Command::GateDrivingVoltage, &[vgh])?;
+            self.cmd_with_data(spi, Command::SourceDrivingVoltage, &[vsh1, vsh2, vsl])?;
+            self.cmd_with_data(spi, Command::WriteVcomRegister, &[vcom])?;
+        }
+        Ok(())
+    }
+
+    fn write_ram(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        command: Command,
+        buffer: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_counter(spi)?;
+        self.cmd_with_data(spi, command, buffer)
+    }
+
+    /// Sets the RAM window to the whole panel
+    fn set_ram_area(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        self.set_ram_window(spi, 0, 0, self.config.width - 1, self.config.height - 1)
+    }
+
+    /// Sets the address counter to the start of the panel
+    fn set_ram_counter(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        self.set_ram_position(spi, 0, 0)
+    }
+
+    fn set_ram_window(
+        &mut self,
+        spi: &mut SPI,
+        start_x: u32,
+        start_y: u32,
+        end_x: u32,
+        end_y: u32,
+    ) -> Result<(), SPI::Error> {
+        // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
+        // aren't relevant
+        self.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[(start_x >> 3) as u8, (end_x >> 3) as u8],
+        )?;
+
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[
+                start_y as u8,
+                (start_y >> 8) as u8,
+                end_y as u8,
+                (end_y >> 8) as u8,
+            ],
+        )
+    }
+
+    fn set_ram_position(&mut self, spi: &mut SPI, x: u32, y: u32) -> Result<(), SPI::Error> {
+        self.cmd_with_data(spi, Command::SetRamXAddressCounter, &[(x >> 3) as u8])?;
+        self.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8],
+        )
+    }
+}
+
+/// Display update sequence for the given waveform source and mode
+fn display_sequence(custom_lut: bool, refresh: RefreshLut) -> u8 {
+    // load the temperature and the waveform from the OTP unless the LUT was written
+    let load = if custom_lut { 0xC4 } else { 0xF4 };
+    match refresh {
+        RefreshLut::Full | RefreshLut::Gray4 => load | 0x03,
+        RefreshLut::Quick => load | 0x0B,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config() {
+        let config = Config::default();
+        assert_eq!((config.width, config.height), (128, 296));
+        assert_eq!(config.lut, None);
+        assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
+
+        let lut = Lut::new(&[0; LUT_WITH_VOLTAGES_LEN]).unwrap();
+        let config = Config::new(200, 200).with_lut(lut);
+        assert_eq!(config.lut.unwrap().as_bytes().len(), 159);
+    }
+
+    #[test]
+    fn lut_lengths() {
+        assert_eq!(
+            Lut::new(&[1; LUT_LEN]).unwrap().as_bytes(),
+            &[1; LUT_LEN][..]
+        );
+        assert!(Lut::new(&[0; LUT_LEN + 1]).is_none());
+        assert!(Lut::new(&[]).is_none());
+    }
+
+    #[test]
+    fn display_sequences() {
+        assert_eq!(display_sequence(false, RefreshLut::Full), 0xF7);
+        assert_eq!(display_sequence(false, RefreshLut::Quick), 0xFF);
+        assert_eq!(display_sequence(true, RefreshLut::Full), 0xC7);
+        assert_eq!(display_sequence(true, RefreshLut::Quick), 0xCF);
+    }
+}
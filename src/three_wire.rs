@@ -0,0 +1,352 @@
+//! 3-wire SPI (9 bit frames) for boards without a DC pin
+//!
+//! Most controllers can be strapped (BS1 pin) to a 3-wire mode, where the D/C flag is
+//! sent as a ninth bit ahead of every byte instead of on its own line.
+//! [`ThreeWireSpi`] packs these 9 bit frames into the byte stream of an ordinary
+//! 8 bit [`SpiDevice`], the DC level is taken from a [`DcPin`] that is handed to the
+//! driver in place of a real pin. All drivers work unchanged:
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::eh1::*;
+//!# fn main() -> Result<(), embedded_hal::spi::ErrorKind> {
+//!use epd_waveshare::{epd2in9_v2::*, prelude::*, three_wire::*};
+//!#
+//!# let expectations = [];
+//!# let spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let busy_in = digital::Mock::new(&expectations);
+//!# let rst = digital::Mock::new(&expectations);
+//!# let mut delay = delay::NoopDelay::new();
+//!
+//!let dc = DcState::new();
+//!let mut spi = ThreeWireSpi::new(spi, &dc);
+//!let mut epd = Epd2in9::new(&mut spi, busy_in, dc.pin(), rst, &mut delay, None)?;
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+//!
+//! Every transaction is passed on as one transaction of the wrapped device, so CS stays
+//! low throughout. Its writes are sent as one stream of 9 bit frames, the bits filling
+//! up the last byte are zero and dropped by the controller when CS goes high. The frames
+//! are packed into a buffer of the [`ThreeWireSpi`], which holds [`MAX_WRITE_LEN`]
+//! bytes. Reads and transfers are passed on unchanged.
+//!
+//! Longer writes, or more than 8 operations, are split into several transactions of the
+//! wrapped device. CS only goes high between complete frames there, which the
+//! controllers accept like the byte-wise CS toggling of the Waveshare examples.
+
+use core::cell::Cell;
+use core::convert::Infallible;
+use core::iter::Peekable;
+use core::slice::IterMut;
+
+use embedded_hal::{
+    digital::{ErrorType as PinErrorType, OutputPin},
+    spi::{ErrorType, Operation, SpiDevice},
+};
+
+/// Maximum number of bytes written in one transaction of the wrapped device
+pub const MAX_WRITE_LEN: usize = 512;
+/// Packed length of [`MAX_WRITE_LEN`] bytes, 8 bytes fill 9 bytes of frames
+const PACKED_LEN: usize = MAX_WRITE_LEN / 8 * 9;
+/// Maximum number of operations in one transaction of the wrapped device
+const MAX_OPERATIONS: usize = 8;
+
+/// Level of the virtual DC line, shared by [`DcPin`] and [`ThreeWireSpi`]
+#[derive(Debug, Default)]
+pub struct DcState(Cell<bool>);
+
+impl DcState {
+    /// Creates the state, starting with the command level (low)
+    pub const fn new() -> Self {
+        DcState(Cell::new(false))
+    }
+
+    /// The pin to hand to the driver as DC
+    pub fn pin(&self) -> DcPin<'_> {
+        DcPin(&self.0)
+    }
+}
+
+/// Virtual DC pin, only records the level for [`ThreeWireSpi`]
+#[derive(Debug)]
+pub struct DcPin<'a>(&'a Cell<bool>);
+
+impl PinErrorType for DcPin<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for DcPin<'_> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.set(true);
+        Ok(())
+    }
+}
+
+/// [`SpiDevice`] sending every written byte as 9 bit frame with the D/C flag ahead
+#[derive(Debug)]
+pub struct ThreeWireSpi<'a, SPI> {
+    spi: SPI,
+    dc: &'a Cell<bool>,
+    packed: [u8; PACKED_LEN],
+}
+
+impl<'a, SPI> ThreeWireSpi<'a, SPI> {
+    /// Wraps an 8 bit [`SpiDevice`], the D/C flag is taken from `dc`
+    pub fn new(spi: SPI, dc: &'a DcState) -> Self {
+        ThreeWireSpi {
+            spi,
+            dc: &dc.0,
+            packed: [0; PACKED_LEN],
+        }
+    }
+
+    /// Returns the wrapped [`SpiDevice`]
+    pub fn release(self) -> SPI {
+        self.spi
+    }
+}
+
+impl<SPI: SpiDevice> ErrorType for ThreeWireSpi<'_, SPI> {
+    type Error = SPI::Error;
+}
+
+impl<SPI: SpiDevice> SpiDevice for ThreeWireSpi<'_, SPI> {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let dc = self.dc.get();
+        let mut operations = operations.iter_mut().peekable();
+        let mut pending: &[u8] = &[];
+
+        loop {
+            self.inner_transaction(dc, &mut operations, &mut pending)?;
+            if pending.is_empty() && operations.peek().is_none() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<SPI: SpiDevice> ThreeWireSpi<'_, SPI> {
+    /// Passes on as many operations as fit into one transaction of the wrapped device
+    ///
+    /// `pending` holds the rest of a write that didn't fit into the previous one.
+    fn inner_transaction<'o, 'a: 'o>(
+        &mut self,
+        dc: bool,
+        operations: &mut Peekable<IterMut<'o, Operation<'a, u8>>>,
+        pending: &mut &'a [u8],
+    ) -> Result<(), SPI::Error> {
+        let mut inner = [(); MAX_OPERATIONS].map(|_| Operation::DelayNs(0));
+        let mut count = 0;
+        let mut free = &mut self.packed[..];
+
+        while count < MAX_OPERATIONS {
+            if !pending.is_empty() || matches!(operations.peek(), Some(Operation::Write(_))) {
+                // consecutive writes are one stream of frames, padded only at its end
+                let mut packer = FramePacker::new(core::mem::take(&mut free));
+                loop {
+                    let len = packer.push(dc, pending);
+                    *pending = &pending[len..];
+                    if !pending.is_empty() {
+                        break;
+                    }
+                    match operations.next_if(|op| matches!(op, Operation::Write(_))) {
+                        Some(Operation::Write(data)) => *pending = *data,
+                        _ => break,
+                    }
+                }
+                let (packed, rest) = packer.finish();
+                free = rest;
+                inner[count] = Operation::Write(packed);
+                count += 1;
+                if !pending.is_empty() {
+                    // the buffer is full
+                    break;
+                }
+                continue;
+            }
+
+            inner[count] = match operations.next() {
+                Some(Operation::Read(buf)) => Operation::Read(buf),
+                Some(Operation::Transfer(read, write)) => Operation::Transfer(read, write),
+                Some(Operation::TransferInPlace(buf)) => Operation::TransferInPlace(buf),
+                Some(Operation::DelayNs(ns)) => Operation::DelayNs(*ns),
+                Some(Operation::Write(_)) | None => break,
+            };
+            count += 1;
+        }
+
+        self.spi.transaction(&mut inner[..count])
+    }
+}
+
+/// Packs bytes as 9 bit frames (D/C flag, then the byte MSB first) into a buffer
+struct FramePacker<'b> {
+    dst: &'b mut [u8],
+    len: usize,
+    acc: u32,
+    bits: u32,
+}
+
+impl<'b> FramePacker<'b> {
+    fn new(dst: &'b mut [u8]) -> Self {
+        FramePacker {
+            dst,
+            len: 0,
+            acc: 0,
+            bits: 0,
+        }
+    }
+
+    /// Packs the bytes of `src` that fit into the buffer, returns their number
+    fn push(&mut self, dc: bool, src: &[u8]) -> usize {
+        for (i, &byte) in src.iter().enumerate() {
+            // the frame and the padding of the last byte have to fit
+            let bits = self.len * 8 + self.bits as usize + 9;
+            if (bits + 7) / 8 > self.dst.len() {
+                return i;
+            }
+            self.acc = self.acc << 9 | u32::from(dc) << 8 | u32::from(byte);
+            self.bits += 9;
+            while self.bits >= 8 {
+                self.bits -= 8;
+                self.put((self.acc >> self.bits) as u8);
+            }
+        }
+        src.len()
+    }
+
+    fn put(&mut self, byte: u8) {
+        self.dst[self.len] = byte;
+        self.len += 1;
+    }
+
+    /// Pads the last frame, returns the packed bytes and the rest of the buffer
+    fn finish(mut self) -> (&'b mut [u8], &'b mut [u8]) {
+        if self.bits > 0 {
+            self.put((self.acc << (8 - self.bits)) as u8);
+        }
+        self.dst.split_at_mut(self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack_frames(dc: bool, src: &[&[u8]], dst: &mut [u8]) -> usize {
+        let mut packer = FramePacker::new(dst);
+        for src in src {
+            assert_eq!(packer.push(dc, src), src.len());
+        }
+        packer.finish().0.len()
+    }
+
+    #[test]
+    fn frame_packing() {
+        let mut packed = [0; 9];
+        assert_eq!(pack_frames(false, &[&[0x12]], &mut packed), 2);
+        assert_eq!(packed[..2], [0x09, 0x00]);
+
+        assert_eq!(pack_frames(true, &[&[0xFF]], &mut packed), 2);
+        assert_eq!(packed[..2], [0xFF, 0x80]);
+
+        assert_eq!(pack_frames(true, &[&[0x00; 8]], &mut packed), 9);
+        assert_eq!(
+            packed,
+            [0x80, 0x40, 0x20, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00]
+        );
+
+        // no padding between consecutive writes
+        let mut split = [0; 9];
+        assert_eq!(pack_frames(true, &[&[0x00; 3], &[0x00; 5]], &mut split), 9);
+        assert_eq!(split, packed);
+    }
+
+    #[test]
+    fn one_inner_transaction() {
+        use embedded_hal_mock::eh1::spi::{Mock, Transaction};
+        extern crate std;
+        use std::vec;
+
+        let expectations = [
+            Transaction::transaction_start(),
+            Transaction::write_vec(vec![0x09, 0x00]),
+            Transaction::delay(100),
+            Transaction::read_vec(vec![0xAB, 0xCD]),
+            Transaction::transaction_end(),
+        ];
+        let dc = DcState::new();
+        let mut spi = ThreeWireSpi::new(Mock::new(&expectations), &dc);
+
+        let mut read = [0; 2];
+        spi.transaction(&mut [
+            Operation::Write(&[0x12]),
+            Operation::DelayNs(100),
+            Operation::Read(&mut read),
+        ])
+        .unwrap();
+        assert_eq!(read, [0xAB, 0xCD]);
+
+        spi.release().done();
+    }
+
+    #[test]
+    fn split_inner_transactions() {
+        use embedded_hal_mock::eh1::spi::{Mock, Transaction};
+        extern crate std;
+        use std::{vec, vec::Vec};
+
+        // 8 bytes of 0xFF with DC high are 72 set bits
+        let packed = |bytes: usize| {
+            let mut packed = vec![0xFF; bytes / 8 * 9];
+            if bytes % 8 != 0 {
+                packed.extend_from_slice(&[0xFF; 9][..bytes % 8 * 9 / 8]);
+                packed.push(0xFF << (8 - bytes % 8 * 9 % 8));
+            }
+            packed
+        };
+        let mut expectations = vec![
+            Transaction::transaction_start(),
+            Transaction::write_vec(packed(MAX_WRITE_LEN)),
+            Transaction::transaction_end(),
+            Transaction::transaction_start(),
+            Transaction::write_vec(packed(1000 - MAX_WRITE_LEN)),
+        ];
+        expectations.extend((0..MAX_OPERATIONS - 1).map(|_| Transaction::delay(1)));
+        expectations.push(Transaction::transaction_end());
+        expectations.push(Transaction::transaction_start());
+        expectations.push(Transaction::delay(1));
+        expectations.push(Transaction::transaction_end());
+
+        let dc = DcState::new();
+        dc.pin().set_high().unwrap();
+        let mut spi = ThreeWireSpi::new(Mock::new(&expectations), &dc);
+
+        let data = [0xFF; 1000];
+        let mut operations: Vec<_> = vec![
+            Operation::Write(&data[..600]),
+            Operation::Write(&data[600..]),
+        ];
+        operations.extend((0..MAX_OPERATIONS).map(|_| Operation::DelayNs(1)));
+        spi.transaction(&mut operations).unwrap();
+
+        spi.release().done();
+    }
+
+    #[test]
+    fn dc_pin() {
+        let dc = DcState::new();
+        let mut pin = dc.pin();
+        pin.set_high().unwrap();
+        assert!(dc.0.get());
+        pin.set_low().unwrap();
+        assert!(!dc.0.get());
+    }
+}
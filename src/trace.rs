@@ -0,0 +1,248 @@
+//! Protocol trace of what a driver sends to the display
+//!
+//! A [`TraceFn`] set with
+//! [`DisplaySettings::set_trace`](crate::prelude::DisplaySettings::set_trace) gets a
+//! [`TraceEvent`] for every command, data transfer, reset pulse and busy wait, and for
+//! the controller addressed on panels with several ones. The events print in the form
+//! of the Waveshare C sample code, which makes it easy to compare both side by side
+//! when a new panel shows garbage:
+//!
+//!```text
+//!EPD_SendCommand(0x01);
+//!EPD_SendData(0x27); EPD_SendData(0x01); EPD_SendData(0x00);
+//!EPD_SendCommand(0x24);
+//!// EPD_SendData() x 4736: 0xFF 0xFF 0xFF 0xFF 0xFF 0xFF 0xFF 0xFF ...
+//!EPD_ReadBusy(); // ~1520 ms
+//!```
+//!
+//! [`log_trace`] sends them to the `log` or `defmt` sink, a [`TraceBuffer`] keeps the
+//! latest ones in a ring buffer (e.g. in a `static` behind a critical section mutex).
+
+use core::fmt;
+
+/// Number of data bytes kept per [`TraceEvent::Data`]
+pub const DATA_PREVIEW_LEN: usize = 8;
+
+/// Called with every [`TraceEvent`] of a display
+pub type TraceFn = fn(TraceEvent);
+
+/// One step of the protocol
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TraceEvent {
+    /// A command address
+    Command(u8),
+    /// A data transfer, big transfers are summarised with their first bytes
+    Data {
+        /// Number of bytes sent
+        len: usize,
+        /// The first (up to [`DATA_PREVIEW_LEN`]) bytes
+        preview: [u8; DATA_PREVIEW_LEN],
+    },
+    /// The same data byte sent `count` times
+    Repeat {
+        /// The data byte
+        value: u8,
+        /// Number of repetitions
+        count: u32,
+    },
+    /// Data bytes read from the display
+    Read {
+        /// Number of bytes read
+        len: usize,
+    },
+    /// A reset pulse
+    Reset {
+        /// Time RST is held high before the pulse, in us
+        high_us: u32,
+        /// Length of the low pulse, in us
+        low_us: u32,
+        /// Time waited after the pulse, in us
+        wait_us: u32,
+    },
+    /// A wait for the BUSY pin
+    Busy {
+        /// Number of times the pin was found busy
+        polls: u32,
+        /// Approximate duration in us: `polls` times the `delay_us` of the display
+        ///
+        /// `None` if a [`BusyWait`](crate::BusyWait) is used, the time it took is unknown.
+        approx_us: Option<u32>,
+    },
+    /// The following events go to the named controller of a panel with several ones
+    Controller(&'static str),
+}
+
+impl TraceEvent {
+    /// Creates a [`TraceEvent::Data`] of `data`
+    pub fn data(data: &[u8]) -> Self {
+        let mut preview = [0; DATA_PREVIEW_LEN];
+        let n = data.len().min(DATA_PREVIEW_LEN);
+        preview[..n].copy_from_slice(&data[..n]);
+        TraceEvent::Data {
+            len: data.len(),
+            preview,
+        }
+    }
+}
+
+/// Prints the event like the Waveshare C sample code
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TraceEvent::Command(command) => write!(f, "EPD_SendCommand(0x{:02X});", command),
+            TraceEvent::Data { len, preview } if len <= DATA_PREVIEW_LEN => {
+                for (i, byte) in preview[..len].iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "EPD_SendData(0x{:02X});", byte)?;
+                }
+                Ok(())
+            }
+            TraceEvent::Data { len, preview } => {
+                write!(f, "// EPD_SendData() x {}:", len)?;
+                for byte in preview {
+                    write!(f, " 0x{:02X}", byte)?;
+                }
+                f.write_str(" ...")
+            }
+            TraceEvent::Repeat { value, count } => write!(
+                f,
+                "for (UDOUBLE i = 0; i < {}; i++) EPD_SendData(0x{:02X});",
+                count, value
+            ),
+            TraceEvent::Read { len } => write!(f, "// read {} bytes", len),
+            TraceEvent::Reset {
+                high_us,
+                low_us,
+                wait_us,
+            } => write!(
+                f,
+                "DEV_Digital_Write(EPD_RST_PIN, 1); DEV_Delay_ms({}); \
+                 DEV_Digital_Write(EPD_RST_PIN, 0); DEV_Delay_ms({}); \
+                 DEV_Digital_Write(EPD_RST_PIN, 1); DEV_Delay_ms({});",
+                high_us / 1000,
+                low_us / 1000,
+                wait_us / 1000
+            ),
+            TraceEvent::Busy {
+                approx_us: Some(approx_us),
+                ..
+            } => write!(f, "EPD_ReadBusy(); // ~{} ms", approx_us / 1000),
+            TraceEvent::Busy {
+                approx_us: None, ..
+            } => f.write_str("EPD_ReadBusy();"),
+            TraceEvent::Controller(name) => write!(f, "// {}:", name),
+        }
+    }
+}
+
+/// A [`TraceFn`] sending every event to the `log` or `defmt` sink (debug level)
+///
+/// `defmt` prints the `defmt::Format` of the event instead of the C form.
+/// Without either feature the events are dropped.
+pub fn log_trace(event: TraceEvent) {
+    debug!("{}", event);
+}
+
+/// Ring buffer keeping the latest `N` events
+#[derive(Clone, Debug)]
+pub struct TraceBuffer<const N: usize> {
+    events: [Option<TraceEvent>; N],
+    next: usize,
+}
+
+impl<const N: usize> TraceBuffer<N> {
+    /// Creates an empty buffer
+    pub const fn new() -> Self {
+        TraceBuffer {
+            events: [None; N],
+            next: 0,
+        }
+    }
+
+    /// Adds an event, dropping the oldest one if the buffer is full
+    pub fn push(&mut self, event: TraceEvent) {
+        if N == 0 {
+            return;
+        }
+        self.events[self.next] = Some(event);
+        self.next = (self.next + 1) % N;
+    }
+
+    /// Removes all events
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// The kept events, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &TraceEvent> {
+        let (newer, older) = self.events.split_at(self.next);
+        older.iter().chain(newer).flatten()
+    }
+}
+
+impl<const N: usize> Default for TraceBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn c_form() {
+        assert_eq!(
+            TraceEvent::Command(0x12).to_string(),
+            "EPD_SendCommand(0x12);"
+        );
+        assert_eq!(
+            TraceEvent::data(&[0x27, 0x01]).to_string(),
+            "EPD_SendData(0x27); EPD_SendData(0x01);"
+        );
+        assert_eq!(
+            TraceEvent::data(&[0xFF; 4736]).to_string(),
+            "// EPD_SendData() x 4736: 0xFF 0xFF 0xFF 0xFF 0xFF 0xFF 0xFF 0xFF ..."
+        );
+        assert_eq!(
+            TraceEvent::Busy {
+                polls: 152,
+                approx_us: Some(1_520_000)
+            }
+            .to_string(),
+            "EPD_ReadBusy(); // ~1520 ms"
+        );
+        assert_eq!(
+            TraceEvent::Busy {
+                polls: 3,
+                approx_us: None
+            }
+            .to_string(),
+            "EPD_ReadBusy();"
+        );
+        assert_eq!(TraceEvent::Controller("M1").to_string(), "// M1:");
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let mut buffer = TraceBuffer::<3>::new();
+        assert_eq!(buffer.iter().count(), 0);
+        for command in 0..5 {
+            buffer.push(TraceEvent::Command(command));
+        }
+        let commands: std::vec::Vec<_> = buffer.iter().copied().collect();
+        assert_eq!(
+            commands,
+            [
+                TraceEvent::Command(2),
+                TraceEvent::Command(3),
+                TraceEvent::Command(4)
+            ]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::interface::HasSettings;
 use core::marker::Sized;
 use embedded_hal::{delay::*, digital::*, spi::SpiDevice};
 
@@ -8,6 +9,7 @@ pub(crate) trait Command: Copy {
 }
 
 /// Seperates the different LUT for the Display Refresh process
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, Clone, PartialEq, Eq, Copy, Default)]
 pub enum RefreshLut {
     /// The "normal" full Lookuptable for the Refresh-Sequence
@@ -16,6 +18,10 @@ pub enum RefreshLut {
     /// The quick LUT where not the full refresh sequence is followed.
     /// This might lead to some
     Quick,
+    /// The 4-grey LUT of panels that support grayscale.
+    ///
+    /// Drivers without a grayscale mode use the full LUT instead.
+    Gray4,
 }
 
 pub(crate) trait InternalWiAdditions<SPI, BUSY, DC, RST, DELAY>
@@ -172,14 +178,38 @@ where
     where
         Self: Sized;
 
+    /// Creates a new driver like [`new`](WaveshareDisplay::new), switching the supply on
+    /// with `power` first
+    ///
+    /// The pin is kept, as if given to [`DisplaySettings::set_power_pin`] afterwards.
+    fn new_with_power(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        mut power: crate::PowerPin<RST>,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error>
+    where
+        Self: Sized + DisplaySettings<BUSY, RST>,
+    {
+        power.switch_on(delay);
+        let mut epd = Self::new(spi, busy, dc, rst, delay, delay_us)?;
+        epd.set_power_pin(Some(power));
+        Ok(epd)
+    }
+
     /// Let the device enter deep-sleep mode to save power.
     ///
     /// The deep sleep mode returns to standby with a hardware reset.
+    /// With a [power pin](DisplaySettings::set_power_pin) the supply is switched off too.
     fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
 
     /// Wakes the device up from sleep
     ///
-    /// Also reintialises the device if necessary.
+    /// Also reintialises the device if necessary, after switching the supply on again if
+    /// there is a [power pin](DisplaySettings::set_power_pin).
     fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
 
     /// Sets the backgroundcolor for various commands like [clear_frame](WaveshareDisplay::clear_frame)
@@ -258,6 +288,69 @@ where
     fn wait_until_idle(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
 }
 
+/// Settings of the connection to a display, available on all drivers using it
+///
+/// Every driver but the [it8951](crate::it8951) implements it. It can't be implemented
+/// outside of this crate.
+pub trait DisplaySettings<BUSY, RST>: HasSettings<BUSY, RST> {
+    /// Sets the maximum number of bytes per spi transfer, longer writes are split up
+    ///
+    /// Defaults to [`DEFAULT_MAX_TRANSFER_LEN`](crate::DEFAULT_MAX_TRANSFER_LEN): 4096 bytes
+    /// on Linux (the default `bufsiz` of spidev), unlimited elsewhere. Use it for DMA
+    /// engines with a limit (e.g. 255 or 65535 bytes) or spidev setups with a larger `bufsiz`.
+    /// Zero is treated as one.
+    fn set_max_transfer_len(&mut self, len: usize) {
+        self.settings().max_transfer_len = len.max(1);
+    }
+
+    /// Sets a function or closure that waits for the BUSY pin instead of polling it every
+    /// `delay_us`
+    ///
+    /// It can put the MCU to sleep until the edge interrupt of BUSY fires,
+    /// see [`BusyWait`](crate::BusyWait). `None` goes back to polling.
+    fn set_busy_wait(&mut self, busy_wait: Option<crate::BusyWait<BUSY>>) {
+        self.settings().busy_wait = busy_wait;
+    }
+
+    /// Sets a function receiving a [trace](crate::trace) of everything sent to the display
+    ///
+    /// `None` stops tracing.
+    fn set_trace(&mut self, trace: Option<crate::trace::TraceFn>) {
+        self.settings().trace = trace;
+    }
+
+    /// Sets the [pin switching the supply](crate::PowerPin) of the display, returns the
+    /// previous one
+    ///
+    /// The supply has to be on (the pin high) when the first pin is handed over, e.g.
+    /// before creating the driver, or use [`WaveshareDisplay::new_with_power`]. From then
+    /// on [`sleep`](WaveshareDisplay::sleep) switches it off and
+    /// [`wake_up`](WaveshareDisplay::wake_up) on again. A pin replacing one switched off
+    /// by `sleep` is switched on by the next `wake_up`.
+    fn set_power_pin(
+        &mut self,
+        power: Option<crate::PowerPin<RST>>,
+    ) -> Option<crate::PowerPin<RST>> {
+        core::mem::replace(&mut self.settings().power, power)
+    }
+
+    /// Replaces the [reset timing](crate::ResetProfile) the driver uses when initialising
+    /// the display, `None` goes back to it
+    ///
+    /// This is the reset of [`wake_up`](WaveshareDisplay::wake_up) and of the
+    /// initialisation in `new`. Resets within a driver's refresh sequence, like the one
+    /// before a partial refresh of the Epd 2in9 V2, keep their own timing.
+    /// Most drivers wait a fixed 200ms after the pulse, like the Waveshare C code does.
+    /// Modules that are ready earlier can [wait less](crate::ResetProfile::with_wait) or
+    /// [for BUSY](crate::ResetProfile::with_busy_wait), which speeds up
+    /// [`wake_up`](WaveshareDisplay::wake_up).
+    fn set_reset_profile(&mut self, reset: Option<crate::ResetProfile>) {
+        self.settings().reset = reset;
+    }
+}
+
+impl<T: HasSettings<BUSY, RST>, BUSY, RST> DisplaySettings<BUSY, RST> for T {}
+
 /// Allows quick refresh support for displays that support it; lets you send both
 /// old and new frame data to support this.
 ///
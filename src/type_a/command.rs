@@ -0,0 +1,102 @@
+//! SPI Commands for the Waveshare 2.9" and 1.54" E-Ink Display
+
+use crate::traits;
+
+/// Epd1in54 and Epd2in9 commands
+///
+/// Should rarely (never?) be needed directly.
+///
+/// For more infos about the addresses and what they are doing look into the PDFs.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Driver Output control
+    ///
+    /// 3 Databytes:
+    /// - A\[7:0\]
+    /// - 0.. A\[8\]
+    /// - 0.. B\[2:0\]
+    ///
+    /// Default: Set A\[8:0\] = 0x127 and B\[2:0\] = 0x0
+    DriverOutputControl = 0x01,
+    /// Booster Soft start control
+    ///
+    /// 3 Databytes:
+    /// - 1.. A\[6:0\]
+    /// - 1.. B\[6:0\]
+    /// - 1.. C\[6:0\]
+    ///
+    /// Default: A\[7:0\] = 0xCF, B\[7:0\] = 0xCE, C\[7:0\] = 0x8D
+    BoosterSoftStartControl = 0x0C,
+    /// Set the scanning start position of the gate driver
+    GateScanStartPosition = 0x0F,
+    /// Deep Sleep Mode Control
+    ///
+    /// 1 Databyte:
+    /// - 0.. A\[0\]
+    ///
+    /// Values:
+    /// - A\[0\] = 0: Normal Mode (POR)
+    /// - A\[0\] = 1: Enter Deep Sleep Mode
+    DeepSleepMode = 0x10,
+    /// Data Entry mode setting
+    ///
+    /// Defines the direction in which the address counter is updated
+    DataEntryModeSetting = 0x11,
+    /// Resets the commands and parameters to their S/W Reset default values except R10h-Deep Sleep Mode
+    SwReset = 0x12,
+    /// Write to the temperature register
+    TemperatureSensorControl = 0x1A,
+    /// Activate Display Update Sequence
+    MasterActivation = 0x20,
+    /// Option for Display Update
+    DisplayUpdateControl1 = 0x21,
+    /// Display Update Sequence Option
+    DisplayUpdateControl2 = 0x22,
+    /// After this command, data entries will be written into the RAM until another command is written
+    WriteRam = 0x24,
+    /// Write VCOM register from MCU interface
+    WriteVcomRegister = 0x2C,
+    /// Write LUT register from MCU interface (30 bytes)
+    WriteLutRegister = 0x32,
+    /// Set the number of dummy line period
+    SetDummyLinePeriod = 0x3A,
+    /// Set Gate line width
+    SetGateLineWidth = 0x3B,
+    /// Select border waveform for VBD
+    BorderWaveformControl = 0x3C,
+    /// Specify the start/end positions of the window address in the X direction
+    SetRamXAddressStartEndPosition = 0x44,
+    /// Specify the start/end positions of the window address in the Y direction
+    SetRamYAddressStartEndPosition = 0x45,
+    /// Make initial settings for the RAM X address in the address counter
+    SetRamXAddressCounter = 0x4E,
+    /// Make initial settings for the RAM Y address in the address counter
+    SetRamYAddressCounter = 0x4F,
+    /// This command is an empty command; it does not have any effect on the display module.
+    ///
+    /// However it can be used to terminate Frame Memory Write or Read Commands.
+    Nop = 0xFF,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::DriverOutputControl.address(), 0x01);
+
+        assert_eq!(Command::SetRamXAddressCounter.address(), 0x4E);
+
+        assert_eq!(Command::Nop.address(), 0xFF);
+    }
+}
@@ -0,0 +1,4 @@
+//! Shared controller code of the Type A displays (epd1in54, epd2in9)
+
+pub(crate) mod command;
+pub(crate) mod constants;
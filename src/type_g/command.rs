@@ -0,0 +1,114 @@
+//! SPI Commands for the Waveshare 4-color G E-Ink Displays
+
+use crate::traits;
+
+/// Commands of the G displays (epd1in64g, epd2in36g, epd3in0g, epd4in37g, epd7in3g)
+///
+/// Should rarely (never?) be needed directly.
+///
+/// The names follow the Waveshare C drivers, most of these commands are not documented
+/// in a public datasheet.
+#[allow(dead_code)]
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// Set Resolution, LUT selection, gate scan direction, source shift direction,
+    /// booster switch, soft reset.
+    PanelSetting = 0x00,
+
+    /// Selecting internal and external power
+    PowerSetting = 0x01,
+
+    /// After the Power Off command, the driver will power off following the Power Off
+    /// Sequence; BUSY signal will become "0".
+    PowerOff = 0x02,
+
+    /// Setting Power OFF sequence
+    PowerOffSequenceSetting = 0x03,
+
+    /// Turning On the Power
+    PowerOn = 0x04,
+
+    /// Booster soft start setting of phase 1
+    BoosterSoftStart1 = 0x05,
+
+    /// Booster soft start setting of phase 2
+    BoosterSoftStart2 = 0x06,
+
+    /// This command makes the chip enter the deep-sleep mode to save power.
+    ///
+    /// The only one parameter is a check code, the command would be excuted if check code = 0xA5.
+    DeepSleep = 0x07,
+
+    /// Booster soft start setting of phase 3
+    BoosterSoftStart3 = 0x08,
+
+    /// This command starts transmitting data and write them into SRAM.
+    DataStartTransmission = 0x10,
+
+    /// After this command is issued, driver will refresh display (data/VCOM) according to
+    /// SRAM data and LUT.
+    DisplayRefresh = 0x12,
+
+    /// The command controls the PLL clock frequency.
+    PllControl = 0x30,
+
+    /// Undocumented analog setting of the 3.0" and 4.37" panels
+    AnalogSetting = 0x4D,
+
+    /// This command indicates the interval of Vcom and data output.
+    VcomAndDataIntervalSetting = 0x50,
+
+    /// This command defines non-overlap period of Gate and Source.
+    TconSetting = 0x60,
+
+    /// This command defines alternative resolution.
+    TconResolution = 0x61,
+
+    /// Command header of the 1.64" and 2.36" panels, unlocks the manufacturer commands
+    CmdhSmall = 0x66,
+
+    /// VCOM DC setting during power off (T_VDCS)
+    VcomDcPowerOff = 0x84,
+
+    /// Command header (CMDH), unlocks the manufacturer commands
+    Cmdh = 0xAA,
+
+    /// Undocumented manufacturer command
+    ManufacturerB0 = 0xB0,
+
+    /// Undocumented manufacturer command
+    ManufacturerB4 = 0xB4,
+
+    /// Undocumented manufacturer command
+    ManufacturerB5 = 0xB5,
+
+    /// Power Saving (PWS)
+    PowerSaving = 0xE3,
+
+    /// Undocumented manufacturer command
+    ManufacturerE7 = 0xE7,
+
+    /// Undocumented manufacturer command
+    ManufacturerE9 = 0xE9,
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::PanelSetting.address(), 0x00);
+        assert_eq!(Command::DisplayRefresh.address(), 0x12);
+        assert_eq!(Command::CmdhSmall.address(), 0x66);
+        assert_eq!(Command::Cmdh.address(), 0xAA);
+    }
+}
@@ -0,0 +1,291 @@
+//! Driver of the Waveshare 4-color G displays
+//! (epd1in64g, epd2in36g, epd3in0g, epd4in37g, epd7in3g)
+//!
+//! All G panels share [`EpdG`]. The panel modules only define the size and the
+//! initialisation of their panel in a [`GPanel`] and export the driver as a type alias,
+//! e.g. [`Epd7in3g`](crate::epd7in3g::Epd7in3g).
+//!
+//! # Pixel format
+//!
+//! The G controllers take 2 bits per pixel with the leftmost pixel in the highest bits.
+//! The color codes are defined in the header of every Waveshare C driver
+//! (`EPD_1IN64G_BLACK` in `EPD_1in64g.h` and so on) and are the same for all five panels:
+//! black `0b00`, white `0b01`, yellow `0b10` and red `0b11`. That is the layout of a
+//! [`QuadColor`] buffer, so the driver sends the buffer as it is. A panel with other
+//! codes would need its own color type rather than a conversion on every transfer.
+
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+    spi::SpiDevice,
+};
+
+use crate::color::QuadColor;
+use crate::interface::{DisplayInterface, HasSettings, ResetProfile, Settings};
+use crate::traits::{InternalWiAdditions, RefreshLut, WaveshareDisplay};
+
+pub(crate) mod command;
+use self::command::Command;
+
+const IS_BUSY_LOW: bool = true;
+const SINGLE_BYTE_WRITE: bool = false;
+
+/// Commands with their data, sent in order
+pub(crate) type Sequence = [(Command, &'static [u8])];
+
+/// Size and initialisation of a G panel
+pub struct Panel {
+    /// Width of the display
+    pub(crate) width: u32,
+    /// Height of the display
+    pub(crate) height: u32,
+    /// Reset pulse
+    pub(crate) reset: ResetProfile,
+    /// Initialisation from the Waveshare sample code
+    pub(crate) init: &'static Sequence,
+}
+
+/// A G panel, implemented by the marker types of the panel modules
+pub trait GPanel {
+    /// The properties of the panel
+    const PANEL: Panel;
+}
+
+/// Driver of the G displays, see the panel modules for the aliases of each panel
+///
+pub struct EpdG<P, SPI, BUSY, DC, RST, DELAY> {
+    /// The panel
+    panel: PhantomData<P>,
+    /// Connection Interface
+    interface: DisplayInterface<SPI, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Background Color
+    color: QuadColor,
+}
+
+impl<P, SPI, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, BUSY, DC, RST, DELAY>
+    for EpdG<P, SPI, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Reset the device
+        self.interface
+            .init_reset(delay, P::PANEL.reset, IS_BUSY_LOW);
+        self.wait_until_idle(spi, delay)?;
+        delay.delay_us(30_000);
+
+        for &(command, data) in P::PANEL.init {
+            self.interface.cmd_with_data(spi, command, data)?;
+        }
+        self.wait_until_idle(spi, delay)
+    }
+}
+
+impl<P, SPI, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, BUSY, DC, RST, DELAY>
+    for EpdG<P, SPI, BUSY, DC, RST, DELAY>
+where
+    P: GPanel,
+    SPI: SpiDevice,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = QuadColor;
+
+    fn new(
+        spi: &mut SPI,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+    ) -> Result<Self, SPI::Error> {
+        let interface = DisplayInterface::new(busy, dc, rst, delay_us);
+        let color = QuadColor::White;
+
+        let mut epd = EpdG {
+            panel: PhantomData,
+            interface,
+            color,
+        };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
+        self.interface.power_off(delay);
+        Ok(())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DataStartTransmission, buffer)
+    }
+
+    /// The controller has no partial window: a window covering the whole display is sent
+    /// like [`update_frame`](WaveshareDisplay::update_frame), other windows are ignored
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        if (x, y, width, height) == (0, 0, P::PANEL.width, P::PANEL.height) {
+            self.update_frame(spi, buffer, delay)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.cmd(spi, Command::PowerOn)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.interface
+            .cmd_with_data(spi, Command::DisplayRefresh, &[0x00])?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.interface
+            .cmd_with_data(spi, Command::PowerOff, &[0x00])?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), SPI::Error> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // four pixels of the background color per byte
+        let bg = self.color.get_bits() * 0x55;
+
+        self.wait_until_idle(spi, delay)?;
+        self.interface.cmd(spi, Command::DataStartTransmission)?;
+        self.interface
+            .data_x_times(spi, bg, P::PANEL.width / 4 * P::PANEL.height)?;
+
+        self.display_frame(spi, delay)
+    }
+
+    fn set_background_color(&mut self, color: QuadColor) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &QuadColor {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        P::PANEL.width
+    }
+
+    fn height(&self) -> u32 {
+        P::PANEL.height
+    }
+
+    /// The display has a single waveform, there is no LUT to switch to
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), SPI::Error> {
+        Ok(())
+    }
+
+    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        Ok(())
+    }
+}
+
+impl<P, SPI, BUSY, DC, RST, DELAY> HasSettings<BUSY, RST> for EpdG<P, SPI, BUSY, DC, RST, DELAY> {
+    fn settings(&mut self) -> &mut Settings<BUSY, RST> {
+        self.interface.settings()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_resolution<P: GPanel>() {
+        let (_, data) = P::PANEL
+            .init
+            .iter()
+            .find(|(command, _)| matches!(command, Command::TconResolution))
+            .unwrap();
+        let width = u32::from(data[0]) << 8 | u32::from(data[1]);
+        let height = u32::from(data[2]) << 8 | u32::from(data[3]);
+        assert_eq!((width, height), (P::PANEL.width, P::PANEL.height));
+    }
+
+    #[test]
+    fn init_sequences_match_panel_size() {
+        check_resolution::<crate::epd1in64g::Panel1in64g>();
+        check_resolution::<crate::epd2in36g::Panel2in36g>();
+        check_resolution::<crate::epd3in0g::Panel3in0g>();
+        check_resolution::<crate::epd4in37g::Panel4in37g>();
+        check_resolution::<crate::epd7in3g::Panel7in3g>();
+    }
+
+    #[cfg(all(feature = "graphics", not(feature = "simulator")))]
+    #[test]
+    fn quad_color_buffer_matches_controller_layout() {
+        use crate::graphics::Display;
+        use embedded_graphics_core::{pixelcolor::BinaryColor, prelude::*};
+
+        let mut display = Display::<8, 1, false, 2, QuadColor>::default();
+        let colors = [
+            QuadColor::Black,
+            QuadColor::White,
+            QuadColor::Yellow,
+            QuadColor::Red,
+            QuadColor::Red,
+            QuadColor::Yellow,
+            QuadColor::White,
+            QuadColor::Black,
+        ];
+        for (x, color) in colors.iter().enumerate() {
+            Pixel(Point::new(x as i32, 0), *color)
+                .draw(&mut display)
+                .unwrap();
+        }
+
+        // leftmost pixel in the highest bits, codes as in the headers of the C drivers
+        assert_eq!(display.buffer(), &[0b00_01_10_11, 0b11_10_01_00]);
+        assert_eq!(QuadColor::from(BinaryColor::Off).get_bits(), 0b01);
+    }
+}